@@ -0,0 +1,258 @@
+use std::ops::{Add, Sub};
+
+use crate::{live::LiveSweep, recreate_priority_index, ObjHolder, Timed};
+
+/// How a object that is waiting to play gains priority
+///
+/// `step` must be greater than zero (the `Default` of `D`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aging<D> {
+    /// How long a object has to wait to gain one more position on the priority order
+    pub step: D,
+    /// Max number of positions a object can gain by waiting
+    pub ceiling: usize,
+}
+
+/// A segment of the schedule together with the boost its object had while playing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgedSegment<T> {
+    pub obj: T,
+    /// Number of positions gained by waiting, over its position on the objects running at the
+    /// same time. A boosted object may still stay behind another one that was boosted too
+    pub boost: usize,
+}
+
+/// How much a object waited, only kept while it is on the running_prograns list
+struct Waiting<U, D> {
+    boost: usize,
+    //waiting time not yet turned into boost
+    progress: D,
+    //since when it is waiting, `None` while it plays
+    since: Option<U>,
+}
+
+impl<U, D> Waiting<U, D>
+where U: Copy + Sub<Output = D>,
+      D: Ord + Copy + Default + Add<Output = D> + Sub<Output = D>
+{
+    /// Turn the time waited until `time` into boost
+    fn age(&mut self, time: U, aging: &Aging<D>) {
+        let Some(since) = self.since else {
+            return;
+        };
+
+        let mut waited = self.progress + (time - since);
+        while self.boost < aging.ceiling && waited >= aging.step {
+            self.boost += 1;
+            waited = waited - aging.step;
+        }
+        self.progress = if self.boost < aging.ceiling { waited } else { D::default() };
+        self.since = Some(time);
+    }
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but a object that waits on
+/// the running_prograns list, started but with a higher priority playing, gains one more position
+/// on the priority order every `aging.step` waited, until it has gained `aging.ceiling` positions
+///
+/// The position is the one among the running objects, so the objects that are not running at the
+/// time do not change the result. On a tie of position plus boost the higher position wins.
+/// The boost is kept until the object finishes, so it does not lose it back when it plays.
+/// On `aging.ceiling` zero it is the same of `time_order_by_priority`, but zero length and inverted
+/// objects are never played.
+///
+/// # Panics
+///
+/// If `aging.step` is not greater than zero (the `Default` of `D`)
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_aging, AgedSegment, Aging, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 0, end: 30, priority: 1 },
+///         Obj { start: 0, end: 30, priority: 2 },
+///     ];
+///
+///     //without aging the first one would never play
+///     let ordered = time_order_by_priority_aging(prograns, Aging { step: 10, ceiling: 2 });
+///     assert_eq!(ordered, vec![
+///         AgedSegment { obj: Obj { start: 0, end: 20, priority: 2 }, boost: 0 },
+///         AgedSegment { obj: Obj { start: 20, end: 30, priority: 1 }, boost: 2 },
+///     ]);
+/// ```
+pub fn time_order_by_priority_aging<T, U, D>(vec: Vec::<T>, aging: Aging<D>) -> Vec<AgedSegment<T>>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy + Add<D, Output = U> + Sub<Output = D>,
+      D: Ord + Copy + Default + Add<Output = D> + Sub<Output = D>
+{
+    assert!(aging.step > D::default(), "the aging step must be greater than zero");
+
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    let mut waiting: Vec<Waiting<U, D>> = arena.iter()
+        .map(|_| Waiting { boost: 0, progress: D::default(), since: None })
+        .collect();
+
+    let mut result: Vec<AgedSegment<T>> = Vec::new();
+    let mut sweep = LiveSweep::new(&arena, |_| true);
+    //the boost of the object playing
+    let mut boost = 0;
+    let mut next = sweep.next_time(None);
+
+    while let Some(time) = next {
+        sweep.advance(&arena, time, |reference| {
+            waiting[reference] = Waiting { boost: 0, progress: D::default(), since: Some(time) };
+        });
+
+        for reference in sweep.running() {
+            waiting[reference].age(time, &aging);
+        }
+
+        //the position on the running objects is unique, so it breaks the ties
+        let winner = sweep.running().enumerate()
+            .max_by_key(|(position, reference)| (position + waiting[*reference].boost, *position))
+            .map(|(_, reference)| reference);
+
+        if winner != sweep.playing() {
+            if let Some(obj) = sweep.play(&arena, time, winner) {
+                result.push(AgedSegment { obj, boost });
+            }
+            boost = winner.map_or(0, |reference| waiting[reference].boost);
+        }
+
+        //the one playing stops waiting and the one it cut starts
+        for reference in sweep.running() {
+            let state = &mut waiting[reference];
+            if Some(reference) == winner {
+                state.since = None;
+            } else if state.since.is_none() {
+                state.since = Some(time);
+            }
+        }
+
+        //the next time a waiting object gains boost
+        let boost_at = sweep.running()
+            .map(|reference| &waiting[reference])
+            .filter(|state| state.since.is_some() && state.boost < aging.ceiling)
+            .map(|state| time + (aging.step - state.progress))
+            .min();
+        next = sweep.next_time(boost_at);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_aging, AgedSegment, Aging};
+    use crate::test_util::{complex_example, obj, Obj};
+
+    fn aged(prograns: Vec<Obj>, step: i32, ceiling: usize) -> Vec<(Obj, usize)> {
+        time_order_by_priority_aging(prograns, Aging { step, ceiling })
+            .into_iter()
+            .map(|segment| (segment.obj, segment.boost))
+            .collect()
+    }
+
+    #[test]
+    fn test_ceiling_zero_same_as_batch() {
+        let segments: Vec<Obj> = aged(complex_example(), 10, 0).into_iter().map(|(obj, _)| obj).collect();
+        assert_eq!(segments, time_order_by_priority(complex_example()));
+    }
+
+    ///   |------------- A -------------|   priority 1
+    ///   |------------- B -------------|   priority 2
+    ///   0       20  30                100
+    ///
+    /// A passes B at 20, then B waits 10, passes A back and keeps playing
+    /// as A has no boost left to gain
+    #[test]
+    fn test_aging_alternates() {
+        let prograns = vec![
+            obj(0, 100, 1),
+            obj(0, 100, 2),
+        ];
+
+        assert_eq!(time_order_by_priority_aging(prograns, Aging { step: 10, ceiling: 2 }), vec![
+            AgedSegment { obj: obj(0, 20, 2), boost: 0 },
+            AgedSegment { obj: obj(20, 30, 1), boost: 2 },
+            AgedSegment { obj: obj(30, 100, 2), boost: 1 },
+        ]);
+    }
+
+    /// Objects that never run together with A and B do not change how A passes B
+    #[test]
+    fn test_unrelated_objects() {
+        let expected = aged(vec![obj(0, 100, 1), obj(0, 100, 2)], 10, 2);
+        assert_eq!(expected[1], (obj(20, 30, 1), 2));
+
+        let prograns = vec![
+            obj(0, 100, 1),
+            obj(500, 800, 1),
+            obj(500, 800, 1),
+            obj(0, 100, 2),
+        ];
+        let segments: Vec<(Obj, usize)> = aged(prograns, 10, 2).into_iter().filter(|(obj, _)| obj.end <= 100).collect();
+        assert_eq!(segments, expected);
+    }
+
+    /// On equal priorities the object later on the input is ahead, and one step of boost only
+    /// ties with it, so it keeps playing
+    #[test]
+    fn test_tie() {
+        let prograns = vec![obj(0, 30, 1), obj(0, 30, 1)];
+        assert_eq!(aged(prograns.clone(), 10, 1), vec![(obj(0, 30, 1), 0)]);
+        assert_eq!(aged(prograns, 10, 2), vec![
+            (obj(0, 20, 1), 0),
+            (obj(20, 30, 1), 2),
+        ]);
+    }
+
+    /// A object that finishes when another starts never waits for it, so it gets no boost
+    #[test]
+    fn test_touching() {
+        assert_eq!(aged(vec![obj(0, 10, 2), obj(10, 40, 1), obj(10, 20, 3)], 5, 2), vec![
+            (obj(0, 10, 2), 0),
+            (obj(10, 20, 3), 0),
+            (obj(20, 40, 1), 2),
+        ]);
+    }
+
+    /// Zero length and inverted objects are never played and never block the others
+    #[test]
+    fn test_zero_length_and_inverted() {
+        let prograns = vec![
+            obj(0, 10, 1),
+            obj(5, 5, 9),
+            obj(8, 2, 9),
+        ];
+
+        assert_eq!(aged(prograns, 1, 2), vec![(obj(0, 10, 1), 0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_step() {
+        aged(vec![obj(0, 10, 1)], 0, 1);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{create_time_order_events_by, get_time, recreate_priority_index_by, Boundary, TimedEvent, Timed};
+
+/// A group of objects that overlap, directly or through other objects of the group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster<U> {
+    /// Positions of the objects on the input, in ascending order
+    pub indices: Vec<usize>,
+    pub start: U,
+    pub end: U,
+    /// Maximum number of objects running at the same time
+    pub max_depth: usize,
+}
+
+/// Find the groups of objects that compete with each other, before any of them is cut
+///
+/// A cluster finishes when there is no object running. Objects that only touch (one finishes
+/// when the other starts) do not compete, so they are on different clusters whatever their priorities.
+/// A cluster with `max_depth` of 1 has no conflict at all.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{overlap_clusters, Cluster, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 16, end: 18, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///         Obj { start: 20, end: 22, priority: 1 },
+///     ];
+///
+///     assert_eq!(overlap_clusters(&prograns), vec![
+///         Cluster { indices: vec![0, 2], start: 12, end: 16, max_depth: 2 },
+///         Cluster { indices: vec![1], start: 16, end: 18, max_depth: 1 },
+///         Cluster { indices: vec![3], start: 20, end: 22, max_depth: 1 },
+///     ]);
+/// ```
+pub fn overlap_clusters<T, U>(vec: &[T]) -> Vec<Cluster<U>>
+where T: Timed<U> + PartialOrd,
+      U: Ord + Copy
+{
+    let arena = recreate_priority_index_by(vec.iter().collect(), |(_, a), (_, b)| {
+        a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Less)
+    });
+
+    let mut time_line = create_time_order_events_by(&arena, |obj| (obj.get_start(), obj.get_end()));
+    //the Ends first, so the objects that only touch are not counted together
+    Boundary::HalfOpen.sort(&mut time_line, |reference| {
+        arena[reference].obj.get_start() == arena[reference].obj.get_end()
+    });
+
+    split_clusters(time_line).into_iter().map(|cluster| {
+        let mut indices: Vec<usize> = Vec::new();
+        let mut depth: usize = 0;
+        let mut max_depth: usize = 0;
+
+        for item in cluster.iter() {
+            match item {
+                TimedEvent::Start { time: _, reference } => {
+                    indices.push(arena[*reference].index);
+                    depth += 1;
+                    max_depth = max_depth.max(depth);
+                },
+                TimedEvent::End { time: _, reference: _ } => {
+                    depth = depth.saturating_sub(1);
+                },
+            }
+        }
+        indices.sort_unstable();
+
+        Cluster {
+            indices,
+            //unwrap is safe because a cluster is never empty
+            start: get_time(cluster.first().unwrap()),
+            end: get_time(cluster.last().unwrap()),
+            max_depth,
+        }
+    }).collect()
+}
+
+/// Split the time_line every time there is no object running, each part can be resolved
+/// without knowing the others
+pub(crate) fn split_clusters<U>(time_line: Vec<TimedEvent<U>>) -> Vec<Vec<TimedEvent<U>>> {
+    let mut clusters: Vec<Vec<TimedEvent<U>>> = Vec::new();
+    let mut cluster: Vec<TimedEvent<U>> = Vec::new();
+    let mut running: usize = 0;
+
+    for item in time_line {
+        match item {
+            TimedEvent::Start { .. } => running += 1,
+            TimedEvent::End { .. } => running = running.saturating_sub(1),
+        }
+        cluster.push(item);
+
+        if running == 0 {
+            clusters.push(std::mem::take(&mut cluster));
+        }
+    }
+    if !cluster.is_empty() {
+        clusters.push(cluster);
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{overlap_clusters, Cluster};
+    use crate::test_util::Obj;
+
+    ///       |- B -|             |- D -|
+    ///   |---- A ----|  |------- C -------|  |- E -|
+    ///   0   2     4 5  6        10   12 14  15    16
+    #[test]
+    fn test_overlap_clusters() {
+        let prograns = vec![
+            Obj { start: 0, end: 5, priority: 1 },
+            Obj { start: 2, end: 4, priority: 2 },
+            Obj { start: 6, end: 14, priority: 1 },
+            Obj { start: 10, end: 12, priority: 2 },
+            Obj { start: 15, end: 16, priority: 1 },
+        ];
+
+        assert_eq!(overlap_clusters(&prograns), vec![
+            Cluster { indices: vec![0, 1], start: 0, end: 5, max_depth: 2 },
+            Cluster { indices: vec![2, 3], start: 6, end: 14, max_depth: 2 },
+            Cluster { indices: vec![4], start: 15, end: 16, max_depth: 1 },
+        ]);
+    }
+
+    ///           |-- C --|
+    ///       |------ B ------|
+    ///   |---------- A ----------|
+    ///   0   2   4       8  10  12
+    #[test]
+    fn test_max_depth() {
+        let prograns = vec![
+            Obj { start: 0, end: 12, priority: 1 },
+            Obj { start: 2, end: 10, priority: 2 },
+            Obj { start: 4, end: 8, priority: 3 },
+        ];
+
+        assert_eq!(overlap_clusters(&prograns), vec![
+            Cluster { indices: vec![0, 1, 2], start: 0, end: 12, max_depth: 3 },
+        ]);
+        assert_eq!(overlap_clusters::<Obj, i32>(&[]), vec![]);
+    }
+
+    /// Objects that only touch do not conflict, whatever the priority of the first one
+    #[test]
+    fn test_touching() {
+        for (first, second) in [(2, 1), (1, 2), (1, 1)] {
+            let prograns = vec![
+                Obj { start: 0, end: 5, priority: first },
+                Obj { start: 5, end: 10, priority: second },
+            ];
+
+            assert_eq!(overlap_clusters(&prograns), vec![
+                Cluster { indices: vec![0], start: 0, end: 5, max_depth: 1 },
+                Cluster { indices: vec![1], start: 5, end: 10, max_depth: 1 },
+            ]);
+        }
+    }
+}
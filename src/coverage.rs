@@ -0,0 +1,179 @@
+use std::{iter::Sum, ops::Sub};
+
+use crate::{time_order_by_priority_segments, Segment, Timed};
+
+/// A piece of a object that was given to another one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LostInterval<U> {
+    pub start: U,
+    pub end: U,
+    /// Position on the input of the object that took this piece
+    pub taken_by: usize,
+}
+
+/// How much of a object made into the final schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage<U, D> {
+    /// From the start to the end of the original object
+    pub requested: D,
+    /// Sum of the segments of the object on the final schedule
+    pub granted: D,
+    /// Number of segments of the object on the final schedule
+    pub fragments: usize,
+    /// The pieces of the original object that other objects took, sorted by time
+    pub lost: Vec<LostInterval<U>>,
+}
+
+/// Resolve the objects and report, for each one on the input order, what it asked and what it got
+///
+/// The zero length segments are ignored, they do not grant any time.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{coverage_report, LostInterval, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///     ];
+///
+///     let report = coverage_report(&prograns);
+///     assert_eq!(report[0].requested, 3);
+///     assert_eq!(report[0].granted, 2);
+///     assert_eq!(report[0].lost, vec![LostInterval { start: 14, end: 15, taken_by: 1 }]);
+///     assert_eq!(report[1].granted, 2);
+///     assert!(report[1].lost.is_empty());
+/// ```
+pub fn coverage_report<T, U, D>(vec: &[T]) -> Vec<Coverage<U, D>>
+where T: Timed<U> + PartialOrd,
+      U: Ord + Copy + Sub<Output = D>,
+      D: Sum
+{
+    coverage_from_segments(vec, &time_order_by_priority_segments(vec))
+}
+
+/// Same as [`coverage_report`] for objects that were already resolved with
+/// [`time_order_by_priority_segments`]
+///
+/// `segments` must be sorted and non overlapping
+pub fn coverage_from_segments<T, U, D>(vec: &[T], segments: &[Segment<U>]) -> Vec<Coverage<U, D>>
+where T: Timed<U>,
+      U: Ord + Copy + Sub<Output = D>,
+      D: Sum
+{
+    let segments: Vec<&Segment<U>> = segments.iter().filter(|segment| segment.start < segment.end).collect();
+
+    vec.iter().enumerate().map(|(index, obj)| {
+        let (start, end) = (obj.get_start(), obj.get_end());
+
+        //the segments that happen while this object should be playing
+        let first = segments.partition_point(|segment| segment.end <= start);
+        let overlapping = segments[first..].iter().take_while(|segment| segment.start < end);
+
+        let mut fragments: usize = 0;
+        let mut granted: Vec<D> = Vec::new();
+        let mut lost: Vec<LostInterval<U>> = Vec::new();
+        for segment in overlapping {
+            if segment.source_index == index {
+                fragments += 1;
+                granted.push(segment.end - segment.start);
+            } else {
+                lost.push(LostInterval {
+                    start: segment.start.max(start),
+                    end: segment.end.min(end),
+                    taken_by: segment.source_index,
+                });
+            }
+        }
+
+        Coverage {
+            requested: end - start,
+            granted: granted.into_iter().sum(),
+            fragments,
+            lost,
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{coverage_from_segments, coverage_report, time_order_by_priority_segments, Coverage, LostInterval};
+    use crate::test_util::{obj, Obj};
+
+    /// Same as the complex example of test_example.rs but with minutes since 11:00,
+    /// so the durations can be subtracted
+    fn complex_example() -> Vec<Obj> {
+        vec![
+            obj(0, 30, 1),
+            obj(60, 240, 1),
+            obj(90, 210, 3),
+            obj(120, 180, 4),
+            obj(150, 270, 2),
+            obj(300, 330, 1),
+        ]
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let report: Vec<Coverage<i32, i32>> = coverage_report(&complex_example());
+
+        assert_eq!(report[0], Coverage { requested: 30, granted: 30, fragments: 1, lost: vec![] });
+        assert_eq!(report[1], Coverage {
+            requested: 180,
+            granted: 30,
+            fragments: 1,
+            lost: vec![
+                LostInterval { start: 90, end: 120, taken_by: 2 },
+                LostInterval { start: 120, end: 180, taken_by: 3 },
+                LostInterval { start: 180, end: 210, taken_by: 2 },
+                LostInterval { start: 210, end: 240, taken_by: 4 },
+            ],
+        });
+        assert_eq!(report[2], Coverage {
+            requested: 120,
+            granted: 60,
+            fragments: 2,
+            lost: vec![LostInterval { start: 120, end: 180, taken_by: 3 }],
+        });
+        assert_eq!(report[4], Coverage {
+            requested: 120,
+            granted: 60,
+            fragments: 1,
+            lost: vec![
+                LostInterval { start: 150, end: 180, taken_by: 3 },
+                LostInterval { start: 180, end: 210, taken_by: 2 },
+            ],
+        });
+    }
+
+    /// The granted and the lost time always sum the requested time
+    #[test]
+    fn test_granted_plus_lost_is_requested() {
+        let prograns = complex_example();
+        let segments = time_order_by_priority_segments(&prograns);
+
+        for coverage in coverage_from_segments::<Obj, i32, i32>(&prograns, &segments) {
+            let lost: i32 = coverage.lost.iter().map(|lost| lost.end - lost.start).sum();
+            assert_eq!(coverage.granted + lost, coverage.requested);
+        }
+    }
+}
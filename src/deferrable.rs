@@ -0,0 +1,190 @@
+use std::ops::{Add, Sub};
+
+use crate::{live::LiveSweep, recreate_priority_index, ObjHolder, Timed};
+
+/// A object that may keep its full duration when it is cut
+pub trait Deferrable {
+    /// When `true` the time the object loses to a higher priority is played later, after its end
+    /// if needed, otherwise it is lost as on [`time_order_by_priority`](crate::time_order_by_priority)
+    fn is_deferrable(&self) -> bool;
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the deferrable objects
+/// always play their whole duration, from `start` to `end`
+///
+/// A deferrable object that is cut plays what is left of it once nothing with a higher priority is
+/// playing, so it can finish after its original end and cut, or delay, the objects that come next.
+/// It never starts before its original start. Zero length objects and inverted ones, that start
+/// after their end, are left out and never played.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_deferrable, Deferrable, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///         deferrable: bool,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     impl Deferrable for Obj {
+///         fn is_deferrable(&self) -> bool { self.deferrable }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 0, end: 30, priority: 1, deferrable: true },
+///         Obj { start: 10, end: 20, priority: 2, deferrable: false },
+///     ];
+///
+///     let ordered = time_order_by_priority_deferrable(prograns);
+///     assert_eq!(ordered.iter().map(|obj| (obj.start, obj.end, obj.priority)).collect::<Vec<_>>(), vec![
+///         (0, 10, 1),
+///         (10, 20, 2),
+///         //the 10 lost to the second object are played after the original end
+///         (20, 40, 1),
+///     ]);
+/// ```
+pub fn time_order_by_priority_deferrable<T, U, D>(vec: Vec::<T>) -> Vec<T>
+where T: Timed<U> + PartialOrd + Deferrable + Clone,
+      U: Ord + Copy + Add<D, Output = U> + Sub<Output = D>,
+      D: Ord + Copy + Default + Sub<Output = D>
+{
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    //the deferrable objects only finish when their time is over
+    let mut sweep = LiveSweep::new(&arena, |obj| !obj.is_deferrable());
+
+    //the time left to play of the deferrable objects
+    let mut remaining: Vec<D> = arena.iter().map(|holder| holder.obj.get_end() - holder.obj.get_start()).collect();
+
+    let mut result: Vec<T> = Vec::new();
+    let mut last_time: Option<U> = None;
+    let mut next = sweep.next_time(None);
+
+    while let Some(time) = next {
+        //the deferrable object playing used its time since the last change
+        if let (Some(reference), Some(last_time)) = (sweep.playing(), last_time) {
+            if arena[reference].obj.is_deferrable() {
+                remaining[reference] = remaining[reference] - (time - last_time);
+                if remaining[reference] <= D::default() {
+                    sweep.remove(&arena, reference);
+                }
+            }
+        }
+        last_time = Some(time);
+
+        sweep.advance(&arena, time, |_| {});
+
+        let winner = sweep.running().next_back();
+        result.extend(sweep.play(&arena, time, winner));
+
+        //when the deferrable object playing is over
+        let over_at = winner
+            .filter(|reference| arena[*reference].obj.is_deferrable())
+            .map(|reference| time + remaining[reference]);
+        next = sweep.next_time(over_at);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_deferrable, Deferrable};
+    use crate::test_util::{complex_example, tagged, Tagged};
+
+    //the tag tells if the object is deferrable
+    impl Deferrable for Tagged<bool> {
+        fn is_deferrable(&self) -> bool {
+            self.tag
+        }
+    }
+
+    /// Without deferrable objects it is the same of time_order_by_priority
+    #[test]
+    fn test_no_deferrable_same_as_batch() {
+        let prograns: Vec<Tagged<bool>> = complex_example().into_iter().map(|obj| Tagged { obj, tag: false }).collect();
+        assert_eq!(time_order_by_priority_deferrable(prograns.clone()), time_order_by_priority(prograns));
+    }
+
+    ///   |------ A ------|                  deferrable
+    ///        |- B -|    |---- C ----|      higher priority
+    ///                          |------ E ------|      lower priority
+    ///   0    10    20   30     40   50         70
+    ///
+    /// A is cut twice and finishes at 60, so E only plays from there
+    #[test]
+    fn test_cascade() {
+        let prograns = vec![
+            tagged(0, 30, 1, true),
+            tagged(10, 20, 3, false),
+            tagged(30, 50, 3, false),
+            tagged(40, 70, 0, false),
+        ];
+
+        assert_eq!(time_order_by_priority_deferrable(prograns), vec![
+            tagged(0, 10, 1, true),
+            tagged(10, 20, 3, false),
+            tagged(20, 30, 1, true),
+            tagged(30, 50, 3, false),
+            tagged(50, 60, 1, true),
+            tagged(60, 70, 0, false),
+        ]);
+    }
+
+    /// On equal priorities the object later on the input wins, the deferrable one plays after it
+    #[test]
+    fn test_tie() {
+        let prograns = vec![
+            tagged(0, 20, 1, true),
+            tagged(10, 20, 1, false),
+        ];
+
+        assert_eq!(time_order_by_priority_deferrable(prograns), vec![
+            tagged(0, 10, 1, true),
+            tagged(10, 20, 1, false),
+            tagged(20, 30, 1, true),
+        ]);
+    }
+
+    /// A deferrable object that was never cut finishes on its end, even when another one starts there
+    #[test]
+    fn test_touching() {
+        let prograns = vec![
+            tagged(0, 10, 2, true),
+            tagged(10, 20, 1, false),
+        ];
+
+        assert_eq!(time_order_by_priority_deferrable(prograns), vec![
+            tagged(0, 10, 2, true),
+            tagged(10, 20, 1, false),
+        ]);
+    }
+
+    /// Zero length and inverted objects are left out, even the deferrable ones
+    #[test]
+    fn test_zero_length_and_inverted() {
+        let prograns = vec![
+            tagged(0, 10, 1, false),
+            tagged(5, 5, 9, true),
+            tagged(8, 2, 9, true),
+            tagged(8, 2, 9, false),
+        ];
+
+        assert_eq!(time_order_by_priority_deferrable(prograns), vec![tagged(0, 10, 1, false)]);
+    }
+}
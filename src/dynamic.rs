@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+
+use crate::{live::LiveSweep, recreate_priority_index_by, ObjHolder, Timed};
+
+/// A object whose priority changes with the time
+///
+/// The priority is only read at the start and the end of the objects and at the declared
+/// `breakpoints`, so `priority_at(t)` must hold until the next of these times.
+pub trait DynamicPriority<U> {
+    type Priority: Ord;
+
+    /// The priority from `time` until the next breakpoint
+    fn priority_at(&self, time: U) -> Self::Priority;
+
+    /// The times when the priority may change, the ones outside the object are ignored
+    fn breakpoints(&self) -> Vec<U> {
+        Vec::new()
+    }
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the winner is chosen
+/// again every time the priority of a object may change, so a object can take over another in the
+/// middle of both
+///
+/// On equal priorities the object later on the input wins. Zero length and inverted objects are never played.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_dynamic_priority, DynamicPriority, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///         //the priority goes to `boosted` from this time on
+///         boost_at: i32,
+///         boosted: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl DynamicPriority<i32> for Obj {
+///         type Priority = i32;
+///         fn priority_at(&self, time: i32) -> i32 {
+///             if time < self.boost_at { self.priority } else { self.boosted }
+///         }
+///         fn breakpoints(&self) -> Vec<i32> { vec![self.boost_at] }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 10, end: 20, priority: 1, boost_at: 15, boosted: 3 },
+///         Obj { start: 10, end: 20, priority: 2, boost_at: 20, boosted: 2 },
+///     ];
+///
+///     let ordered = time_order_by_dynamic_priority(prograns);
+///     assert_eq!(ordered.iter().map(|obj| (obj.start, obj.end, obj.priority)).collect::<Vec<_>>(), vec![
+///         (10, 15, 2),
+///         (15, 20, 1),
+///     ]);
+/// ```
+pub fn time_order_by_dynamic_priority<T, U>(vec: Vec::<T>) -> Vec<T>
+where T: Timed<U> + DynamicPriority<U> + Clone,
+      U: Ord + Copy
+{
+    //all the ranks are ties, so the rank is the position on the input
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index_by(vec, |_, _| Ordering::Equal);
+
+    let mut breakpoints: Vec<U> = arena.iter().flat_map(|holder| {
+        let (start, end) = (holder.obj.get_start(), holder.obj.get_end());
+        holder.obj.breakpoints().into_iter().filter(move |time| start < *time && *time < end)
+    }).collect();
+    breakpoints.sort();
+    breakpoints.dedup();
+    let mut breakpoints = breakpoints.into_iter().peekable();
+
+    let mut result: Vec<T> = Vec::new();
+    let mut sweep = LiveSweep::new(&arena, |_| true);
+    let mut next = sweep.next_time(breakpoints.peek().copied());
+
+    while let Some(time) = next {
+        sweep.advance(&arena, time, |_| {});
+        while breakpoints.next_if(|breakpoint| *breakpoint <= time).is_some() {}
+
+        let winner = sweep.running().max_by(|a, b| {
+            arena[*a].obj.priority_at(time).cmp(&arena[*b].obj.priority_at(time))
+                .then(arena[*a].priority.cmp(&arena[*b].priority))
+        });
+        result.extend(sweep.play(&arena, time, winner));
+
+        next = sweep.next_time(breakpoints.peek().copied());
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_dynamic_priority, time_order_by_priority, DynamicPriority};
+    use crate::test_util::{complex_example, tagged, Tagged};
+
+    //the tag is how much the priority grows every 10
+    impl DynamicPriority<i32> for Tagged<i32> {
+        type Priority = i32;
+
+        fn priority_at(&self, time: i32) -> i32 {
+            self.obj.priority + self.tag * ((time - self.obj.start) / 10)
+        }
+
+        fn breakpoints(&self) -> Vec<i32> {
+            if self.tag == 0 {
+                return Vec::new();
+            }
+            (self.obj.start..self.obj.end).step_by(10).collect()
+        }
+    }
+
+    fn fixed(prograns: Vec<crate::test_util::Obj>) -> Vec<Tagged<i32>> {
+        prograns.into_iter().map(|obj| Tagged { obj, tag: 0 }).collect()
+    }
+
+    /// Without breakpoints and with unique priorities it is the same of time_order_by_priority
+    #[test]
+    fn test_unique_priorities_same_as_batch() {
+        let prograns: Vec<Tagged<i32>> = fixed(complex_example());
+        assert_eq!(time_order_by_dynamic_priority(prograns.clone()), time_order_by_priority(prograns));
+    }
+
+    ///   |------------- A -------------|   grows 1 every 10
+    ///         |-------- B --------|       fixed on 3
+    ///   0     10        40        50  60
+    ///
+    /// A ties with B at 30, B keeps playing as it is later on the input, and A passes B at 40
+    #[test]
+    fn test_cross_over() {
+        let prograns = vec![
+            tagged(0, 60, 0, 1),
+            tagged(10, 50, 3, 0),
+        ];
+
+        assert_eq!(time_order_by_dynamic_priority(prograns), vec![
+            tagged(0, 10, 0, 1),
+            tagged(10, 40, 3, 0),
+            tagged(40, 60, 0, 1),
+        ]);
+    }
+
+    /// On a tie the object later on the input wins, so A does not take over B when it starts
+    #[test]
+    fn test_tie() {
+        let prograns = vec![
+            tagged(5, 20, 1, 0),
+            tagged(0, 10, 1, 0),
+        ];
+
+        assert_eq!(time_order_by_dynamic_priority(prograns), vec![
+            tagged(0, 10, 1, 0),
+            tagged(10, 20, 1, 0),
+        ]);
+    }
+
+    /// A object that finishes when another starts gives its place without a zero length segment
+    #[test]
+    fn test_touching() {
+        let prograns = vec![
+            tagged(10, 20, 1, 0),
+            tagged(0, 10, 2, 0),
+        ];
+
+        assert_eq!(time_order_by_dynamic_priority(prograns), vec![
+            tagged(0, 10, 2, 0),
+            tagged(10, 20, 1, 0),
+        ]);
+    }
+
+    /// Zero length and inverted objects are never played and never block the others
+    #[test]
+    fn test_zero_length_and_inverted() {
+        let prograns = vec![
+            tagged(0, 10, 1, 0),
+            tagged(5, 5, 9, 0),
+            tagged(8, 2, 9, 0),
+        ];
+
+        assert_eq!(time_order_by_dynamic_priority(prograns), vec![tagged(0, 10, 1, 0)]);
+    }
+}
@@ -0,0 +1,37 @@
+use std::fmt::Display;
+
+/// Errors of [`try_time_order_by_priority`](crate::try_time_order_by_priority) and the other
+/// fallible functions of this crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TpoError {
+    /// The object on `index` of the input starts after it finishes
+    InvertedInterval { index: usize },
+    /// The objects on `first` and `second` of the input can not be compared,
+    /// `partial_cmp` returned `None`
+    IncomparablePriorities { first: usize, second: usize },
+    /// The algorithm got into a state that should be impossible
+    InvariantViolation(&'static str),
+    /// The segment on `index` starts before the previous one finishes
+    OverlappingSegments { index: usize },
+}
+
+impl Display for TpoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TpoError::InvertedInterval { index } => {
+                write!(f, "the object {index} starts after it finishes")
+            },
+            TpoError::IncomparablePriorities { first, second } => {
+                write!(f, "the priorities of the objects {first} and {second} can not be compared")
+            },
+            TpoError::InvariantViolation(description) => {
+                write!(f, "internal invariant violated: {description}")
+            },
+            TpoError::OverlappingSegments { index } => {
+                write!(f, "the segment {index} starts before the previous one finishes")
+            },
+        }
+    }
+}
+
+impl std::error::Error for TpoError {}
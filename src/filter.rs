@@ -0,0 +1,302 @@
+use std::ops::Sub;
+
+use crate::{time_order_by_priority, time_order_by_priority_iter, Fragment, Timed};
+
+/// What to do with a segment shorter than the minimum length
+///
+/// Only segments touching another one (one finishes exactly when the other starts) are
+/// considered flicker, a short segment with gaps on both sides is kept as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortSegmentRule {
+    /// The previous segment is extended until the end of the short one,
+    /// if the previous does not touch it the next one is extended instead
+    ExtendPrevious,
+    /// The next segment is extended back to the start of the short one,
+    /// if the next does not touch it the previous one is extended instead
+    ExtendNext,
+    /// The short segment is removed, leaving a gap on its place
+    Drop,
+}
+
+/// Absorb the segments shorter than `min_length` on a neighbouring segment following `rule`
+///
+/// `segments` must be sorted and non overlapping, like the result of
+/// [`time_order_by_priority`](crate::time_order_by_priority).
+/// The segments are processed from the first to the last so a short segment that was
+/// extended by a neighbour is checked again with its new length.
+pub fn absorb_short_segments<T, U, D>(segments: Vec<T>, min_length: D, rule: ShortSegmentRule) -> Vec<T>
+where T: Timed<U>,
+      U: Ord + Copy + Sub<Output = D>,
+      D: Ord
+{
+    absorb_short_segments_by(segments, |start, end| end - start < min_length, rule)
+}
+
+/// Same as [`absorb_short_segments`] but a segment is short when `is_short(start, end)` is true
+pub(crate) fn absorb_short_segments_by<T, U, F>(segments: Vec<T>, is_short: F, rule: ShortSegmentRule) -> Vec<T>
+where T: Timed<U>,
+      U: Ord + Copy,
+      F: Fn(U, U) -> bool
+{
+    let mut result: Vec<T> = Vec::with_capacity(segments.len());
+    let mut inter = segments.into_iter().peekable();
+
+    while let Some(segment) = inter.next() {
+        if !is_short(segment.get_start(), segment.get_end()) {
+            result.push(segment);
+            continue;
+        }
+
+        let previous = result.last_mut()
+            .filter(|previous| previous.get_end() == segment.get_start());
+        let next = inter.peek_mut()
+            .filter(|next| next.get_start() == segment.get_end());
+
+        match (rule, previous, next) {
+            (ShortSegmentRule::Drop, Some(_), _) | (ShortSegmentRule::Drop, _, Some(_)) => {},
+            (ShortSegmentRule::ExtendPrevious, Some(previous), _) | (ShortSegmentRule::ExtendNext, Some(previous), None) => {
+                previous.set_end(segment.get_end());
+            },
+            (ShortSegmentRule::ExtendNext, _, Some(next)) | (ShortSegmentRule::ExtendPrevious, None, Some(next)) => {
+                next.set_start(segment.get_start());
+            },
+            (_, None, None) => result.push(segment),
+        }
+    }
+
+    result
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but without segments
+/// shorter than `min_length`, see [`absorb_short_segments`]
+pub fn time_order_by_priority_with_min_length<T, U, D>(vec: Vec::<T>, min_length: D, rule: ShortSegmentRule) -> Vec<T>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy + Sub<Output = D>,
+      D: Ord
+{
+    absorb_short_segments(time_order_by_priority(vec), min_length, rule)
+}
+
+/// Fill every idle span between `segments` with a object created by `filler(start, end)`
+///
+/// `segments` must be sorted and non overlapping, like the result of
+/// [`time_order_by_priority`](crate::time_order_by_priority).
+/// Nothing is created before the first segment or after the last one.
+pub fn fill_gaps<T, U, F>(segments: Vec<T>, filler: F) -> Vec<T>
+where T: Timed<U>,
+      U: Ord + Copy,
+      F: Fn(U, U) -> T
+{
+    let mut result: Vec<T> = Vec::with_capacity(segments.len());
+
+    for segment in segments {
+        if let Some(previous) = result.last() {
+            if previous.get_end() < segment.get_start() {
+                result.push(filler(previous.get_end(), segment.get_start()));
+            }
+        }
+        result.push(segment);
+    }
+
+    result
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but without gaps between
+/// the first start and the last end, see [`fill_gaps`]
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_with_filler, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 13, priority: 1 },
+///         Obj { start: 14, end: 15, priority: 1 },
+///     ];
+///
+///     let filled = time_order_by_priority_with_filler(prograns, |start, end| Obj { start, end, priority: 0 });
+///
+///     assert_eq!(filled, vec![
+///         Obj { start: 12, end: 13, priority: 1 },
+///         Obj { start: 13, end: 14, priority: 0 },
+///         Obj { start: 14, end: 15, priority: 1 },
+///     ]);
+/// ```
+pub fn time_order_by_priority_with_filler<T, U, F>(vec: Vec::<T>, filler: F) -> Vec<T>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy,
+      F: Fn(U, U) -> T
+{
+    fill_gaps(time_order_by_priority(vec), filler)
+}
+
+/// Remove the zero length fragments and merge the fragments of the same original object
+/// that touch each other
+///
+/// `fragments` must be sorted and non overlapping
+pub(crate) fn merge_adjacent_fragments<U>(fragments: Vec<Fragment<U>>) -> Vec<Fragment<U>>
+where U: Ord + Copy
+{
+    let mut result: Vec<Fragment<U>> = Vec::with_capacity(fragments.len());
+
+    for fragment in fragments.into_iter().filter(|fragment| fragment.start != fragment.end) {
+        match result.last_mut() {
+            Some(previous) if previous.reference == fragment.reference && previous.end == fragment.start => {
+                previous.end = fragment.end;
+            },
+            _ => result.push(fragment),
+        }
+    }
+
+    result
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the touching segments
+/// that came from the same original object are merged in one and the zero length segments are removed
+///
+/// For example when a higher priority object starts and finishes at the same time:
+/// ```text
+///                 |
+///     |---------- A ----------|
+///   12:00       13:00       14:00
+///
+/// time_order_by_priority gives:
+///
+///     |---- A ----|---- A ----|
+///   12:00       13:00       14:00
+///
+/// and this function gives:
+///
+///     |---------- A ----------|
+///   12:00       13:00       14:00
+/// ```
+pub fn time_order_by_priority_merge_adjacent<T, U>(vec: Vec::<T>) -> Vec<T>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    let (arena, fragments) = time_order_by_priority_iter(vec).into_fragments();
+
+    merge_adjacent_fragments(fragments)
+        .into_iter()
+        .map(|fragment| fragment.into_obj(&arena))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{absorb_short_segments, fill_gaps, time_order_by_priority, time_order_by_priority_merge_adjacent, time_order_by_priority_with_min_length, ShortSegmentRule};
+    use crate::test_util::Obj;
+
+    ///   |---- A ----|-B-|---- C ----|       |-D-|
+    ///   0          10  11          20      30  31
+    fn segments() -> Vec<Obj> {
+        vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 10, end: 11, priority: 2 },
+            Obj { start: 11, end: 20, priority: 3 },
+            Obj { start: 30, end: 31, priority: 4 },
+        ]
+    }
+
+    #[test]
+    fn test_extend_previous() {
+        assert_eq!(absorb_short_segments(segments(), 5, ShortSegmentRule::ExtendPrevious), vec![
+            Obj { start: 0, end: 11, priority: 1 },
+            Obj { start: 11, end: 20, priority: 3 },
+            Obj { start: 30, end: 31, priority: 4 },
+        ]);
+    }
+
+    #[test]
+    fn test_extend_next() {
+        assert_eq!(absorb_short_segments(segments(), 5, ShortSegmentRule::ExtendNext), vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 10, end: 20, priority: 3 },
+            Obj { start: 30, end: 31, priority: 4 },
+        ]);
+    }
+
+    #[test]
+    fn test_drop() {
+        assert_eq!(absorb_short_segments(segments(), 5, ShortSegmentRule::Drop), vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 11, end: 20, priority: 3 },
+            Obj { start: 30, end: 31, priority: 4 },
+        ]);
+    }
+
+    /// The zero length segment created when 2 objects start at the same time
+    /// is absorbed by the next one even when the rule is to extend the previous
+    #[test]
+    fn test_zero_length_segment() {
+        let prograns = vec![
+            Obj { start: 12, end: 13, priority: 1 },
+            Obj { start: 12, end: 13, priority: 0 },
+        ];
+
+        assert_eq!(time_order_by_priority_with_min_length(prograns, 1, ShortSegmentRule::ExtendPrevious), vec![
+            Obj { start: 12, end: 13, priority: 1 },
+        ]);
+    }
+
+    ///           |
+    ///   |------ A ------|      |-- B --|
+    ///   0       5       10     12      14
+    #[test]
+    fn test_merge_adjacent() {
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 5, end: 5, priority: 2 },
+            Obj { start: 12, end: 14, priority: 1 },
+        ];
+
+        assert_eq!(time_order_by_priority(prograns.clone()).len(), 4);
+
+        assert_eq!(time_order_by_priority_merge_adjacent(prograns), vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 12, end: 14, priority: 1 },
+        ]);
+    }
+
+    /// Equal objects are only merged when they are the same original object
+    #[test]
+    fn test_merge_adjacent_different_objects() {
+        let prograns = vec![
+            Obj { start: 0, end: 5, priority: 1 },
+            Obj { start: 5, end: 10, priority: 1 },
+        ];
+
+        assert_eq!(time_order_by_priority_merge_adjacent(prograns.clone()), prograns);
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let filled = fill_gaps(segments(), |start, end| Obj { start, end, priority: 0 });
+
+        assert_eq!(filled, vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 10, end: 11, priority: 2 },
+            Obj { start: 11, end: 20, priority: 3 },
+            Obj { start: 20, end: 30, priority: 0 },
+            Obj { start: 30, end: 31, priority: 4 },
+        ]);
+    }
+}
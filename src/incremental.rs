@@ -0,0 +1,354 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    cluster::split_clusters, create_time_order_events_by, get_time, iter::Sweep, recreate_priority_index_by, Schedule,
+    TimedEvent, Timed,
+};
+
+/// A overlap cluster already resolved
+struct ResolvedCluster<T, U> {
+    /// The ids of the items, sorted
+    ids: Vec<usize>,
+    start: U,
+    end: U,
+    schedule: Schedule<T, U>,
+}
+
+/// The segments that left and entered the schedule on a change, both sorted by time
+///
+/// Segments that are the same before and after the change are not on the lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleUpdate<T> {
+    pub removed: Vec<T>,
+    pub added: Vec<T>,
+}
+
+/// A schedule that can change one item at a time
+///
+/// The items are kept grouped on overlap clusters (see [`overlap_clusters`](crate::overlap_clusters)),
+/// a change only resolves again the clusters it touches. The result is always the same of
+/// [`time_order_by_priority`](crate::time_order_by_priority) over the items on the order they were
+/// inserted, so on equal priorities the last inserted wins.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{IncrementalSchedule, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let mut schedule = IncrementalSchedule::new();
+///     let (a, _) = schedule.insert(Obj { start: 12, end: 15, priority: 1 });
+///     let (_, update) = schedule.insert(Obj { start: 14, end: 16, priority: 2 });
+///
+///     assert_eq!(update.removed, vec![Obj { start: 12, end: 15, priority: 1 }]);
+///     assert_eq!(update.added, vec![
+///         Obj { start: 12, end: 14, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///     ]);
+///
+///     let (removed, update) = schedule.remove(a).unwrap();
+///     assert_eq!(removed, Obj { start: 12, end: 15, priority: 1 });
+///     assert_eq!(update.removed, vec![Obj { start: 12, end: 14, priority: 1 }]);
+///     assert!(update.added.is_empty());
+/// ```
+pub struct IncrementalSchedule<T, U> {
+    items: BTreeMap<usize, T>,
+    //by start, the clusters that start at the same time (the zero length ones first) are
+    //always resolved together and kept on the time_line order
+    clusters: BTreeMap<U, Vec<ResolvedCluster<T, U>>>,
+    next_id: usize,
+}
+
+impl<T, U> Default for IncrementalSchedule<T, U>
+where T: Timed<U> + PartialOrd + PartialEq + Clone,
+      U: Ord + Copy
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U> IncrementalSchedule<T, U>
+where T: Timed<U> + PartialOrd + PartialEq + Clone,
+      U: Ord + Copy
+{
+    pub fn new() -> Self {
+        Self {
+            items: BTreeMap::new(),
+            clusters: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Add a item and give back its id, used to remove it, and what changed on the schedule
+    pub fn insert(&mut self, item: T) -> (usize, ScheduleUpdate<T>) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let (start, end) = (item.get_start(), item.get_end());
+        self.items.insert(id, item);
+
+        //every cluster the item overlaps or touches is joined with it
+        let keys = self.touching(start, end);
+        (id, self.update(keys, vec![id]))
+    }
+
+    /// Remove the item with `id` and give it back with what changed on the schedule,
+    /// `None` if there is no item with this id
+    pub fn remove(&mut self, id: usize) -> Option<(T, ScheduleUpdate<T>)> {
+        let item = self.items.remove(&id)?;
+
+        //the cluster of the item starts before or when the item starts, it is resolved again
+        //with the ones that touch it, as they can join or split on the same instant
+        let keys = self.clusters.range(..=item.get_start()).rev()
+            .flat_map(|(_, clusters)| clusters.iter())
+            .find(|cluster| cluster.ids.binary_search(&id).is_ok())
+            .map(|cluster| self.touching(cluster.start, cluster.end))
+            .unwrap_or_default();
+
+        Some((item, self.update(keys, Vec::new())))
+    }
+
+    /// The item with `id`, as it was inserted
+    pub fn get(&self, id: usize) -> Option<&T> {
+        self.items.get(&id)
+    }
+
+    /// The segment playing at `time`
+    pub fn at(&self, time: U) -> Option<&T> {
+        let clusters = self.clusters.range(..=time).rev().flat_map(|(_, clusters)| clusters.iter().rev());
+        for cluster in clusters {
+            //the ones before it finish even earlier, the zero length ones never play
+            if cluster.start < cluster.end && cluster.end <= time {
+                break;
+            }
+            if let Some(segment) = cluster.schedule.at(time) {
+                return Some(segment);
+            }
+        }
+        None
+    }
+
+    /// All segments sorted by time
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.clusters.values().flatten().flat_map(|cluster| cluster.schedule.iter())
+    }
+
+    /// The whole schedule
+    pub fn to_schedule(&self) -> Schedule<T, U> {
+        Schedule::from_sorted(self.iter().cloned().collect())
+    }
+
+    /// Number of items
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The starts of the clusters that overlap or touch `start..=end`, from the last one
+    fn touching(&self, start: U, end: U) -> Vec<U> {
+        self.clusters.range(..=end).rev()
+            //the last cluster of a start is the one that finishes later
+            .take_while(|(_, clusters)| clusters.last().is_some_and(|cluster| cluster.end >= start))
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    /// Resolve again the clusters that start on `keys` together with the items on `ids`
+    fn update(&mut self, mut keys: Vec<U>, mut ids: Vec<usize>) -> ScheduleUpdate<T> {
+        keys.sort();
+
+        let mut before: Vec<T> = Vec::new();
+        for key in keys {
+            //unwrap is safe because the keys came from the map
+            for cluster in self.clusters.remove(&key).unwrap() {
+                ids.extend(cluster.ids);
+                before.extend(cluster.schedule.into_vec());
+            }
+        }
+        //the removed item is no longer on items
+        ids.retain(|id| self.items.contains_key(id));
+        ids.sort_unstable();
+
+        let mut after: Vec<T> = Vec::new();
+        for cluster in self.resolve(ids) {
+            after.extend(cluster.schedule.iter().cloned());
+            self.clusters.entry(cluster.start).or_default().push(cluster);
+        }
+
+        compare(before, after)
+    }
+
+    /// Resolve the items on `ids`, that must be sorted, splitting them on clusters
+    fn resolve(&self, ids: Vec<usize>) -> Vec<ResolvedCluster<T, U>> {
+        let items: Vec<&T> = ids.iter().map(|id| &self.items[id]).collect();
+
+        let arena = recreate_priority_index_by(items, |(_, a), (_, b)| {
+            a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Less)
+        });
+
+        let time_line = create_time_order_events_by(&arena, |obj| (obj.get_start(), obj.get_end()));
+
+        split_clusters(time_line).into_iter().map(|time_line| {
+            let mut cluster_ids: Vec<usize> = time_line.iter().filter_map(|item| match item {
+                TimedEvent::Start { time: _, reference } => Some(ids[arena[*reference].index]),
+                TimedEvent::End { time: _, reference: _ } => None,
+            }).collect();
+            cluster_ids.sort_unstable();
+            //unwrap is safe because a cluster is never empty
+            let (start, end) = (get_time(&time_line[0]), get_time(time_line.last().unwrap()));
+
+            let segments = Sweep::new(time_line).into_fragments(&arena).into_iter().map(|fragment| {
+                let mut obj = T::clone(arena[fragment.reference].obj);
+                obj.set_start(fragment.start);
+                obj.set_end(fragment.end);
+                obj
+            }).collect();
+
+            ResolvedCluster {
+                ids: cluster_ids,
+                start,
+                end,
+                schedule: Schedule::from_sorted(segments),
+            }
+        }).collect()
+    }
+}
+
+/// The segments only on `before` and only on `after`, both must be sorted
+fn compare<T, U>(before: Vec<T>, after: Vec<T>) -> ScheduleUpdate<T>
+where T: Timed<U> + PartialEq,
+      U: Ord + Copy
+{
+    let mut update = ScheduleUpdate { removed: Vec::new(), added: Vec::new() };
+    let mut before = before.into_iter().peekable();
+    let mut after = after.into_iter().peekable();
+
+    loop {
+        match (before.peek(), after.peek()) {
+            (Some(old), Some(new)) if old == new => {
+                before.next();
+                after.next();
+            },
+            (Some(old), Some(new)) if old.get_start() <= new.get_start() => {
+                //unwrap is safe because peek just returned a element
+                update.removed.push(before.next().unwrap());
+            },
+            (_, Some(_)) => update.added.push(after.next().unwrap()),
+            (Some(_), None) => update.removed.push(before.next().unwrap()),
+            (None, None) => break,
+        }
+    }
+
+    update
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{time_order_by_priority, IncrementalSchedule, Schedule};
+    use crate::test_util::{complex_example, obj, random_objs, Obj};
+
+    #[test]
+    fn test_same_result_as_batch() {
+        let mut schedule = IncrementalSchedule::new();
+        let mut ids = Vec::new();
+        for (index, item) in complex_example().into_iter().enumerate() {
+            ids.push(schedule.insert(item).0);
+
+            let inserted = complex_example()[..=index].to_vec();
+            assert_eq!(schedule.iter().cloned().collect::<Vec<Obj>>(), time_order_by_priority(inserted));
+        }
+
+        //remove the odd ones
+        for id in ids.iter().skip(1).step_by(2) {
+            schedule.remove(*id).unwrap();
+        }
+        let remaining: Vec<Obj> = complex_example().into_iter().step_by(2).collect();
+        assert_eq!(schedule.len(), remaining.len());
+        assert_eq!(schedule.to_schedule().into_vec(), time_order_by_priority(remaining));
+    }
+
+    /// Only the cluster of the change is reported
+    #[test]
+    fn test_update_is_local() {
+        let mut schedule = IncrementalSchedule::new();
+        schedule.insert(Obj { start: 0, end: 10, priority: 1 });
+        schedule.insert(Obj { start: 20, end: 30, priority: 1 });
+
+        let (id, update) = schedule.insert(Obj { start: 22, end: 24, priority: 2 });
+        assert_eq!(update.removed, vec![Obj { start: 20, end: 30, priority: 1 }]);
+        assert_eq!(update.added, vec![
+            Obj { start: 20, end: 22, priority: 1 },
+            Obj { start: 22, end: 24, priority: 2 },
+            Obj { start: 24, end: 30, priority: 1 },
+        ]);
+        assert_eq!(schedule.at(23), Some(&Obj { start: 22, end: 24, priority: 2 }));
+        assert_eq!(schedule.at(5), Some(&Obj { start: 0, end: 10, priority: 1 }));
+        assert_eq!(schedule.at(15), None);
+
+        assert!(schedule.remove(id).is_some());
+        assert!(schedule.remove(id).is_none());
+        assert_eq!(schedule.at(23), Some(&Obj { start: 20, end: 30, priority: 1 }));
+    }
+
+    /// A zero length cluster that starts with another one comes first, as on the batch
+    #[test]
+    fn test_zero_length_on_the_same_start() {
+        let mut schedule = IncrementalSchedule::new();
+        schedule.insert(obj(13, 16, 3));
+        schedule.insert(obj(13, 13, 1));
+
+        let expected = time_order_by_priority(vec![obj(13, 16, 3), obj(13, 13, 1)]);
+        assert_eq!(schedule.iter().cloned().collect::<Vec<Obj>>(), expected);
+        assert!(Schedule::try_from_segments(schedule.to_schedule().into_vec()).is_ok());
+        assert_eq!(schedule.at(14), Some(&obj(13, 16, 3)));
+    }
+
+    /// Random inserts and removes always give the same of resolving all the items again
+    #[test]
+    fn test_random_against_batch() {
+        let mut rng = StdRng::seed_from_u64(21);
+        for _ in 0..300 {
+            let mut schedule = IncrementalSchedule::new();
+            let mut inserted: Vec<(usize, Obj)> = Vec::new();
+
+            for item in random_objs(&mut rng, 20) {
+                if !inserted.is_empty() && rng.gen_bool(0.3) {
+                    let (id, removed) = inserted.remove(rng.gen_range(0..inserted.len()));
+                    assert_eq!(schedule.remove(id).map(|(item, _)| item), Some(removed));
+                } else {
+                    inserted.push((schedule.insert(item.clone()).0, item));
+                }
+
+                let items: Vec<Obj> = inserted.iter().map(|(_, item)| item.clone()).collect();
+                let expected = Schedule::resolve(items.clone());
+                assert_eq!(schedule.to_schedule(), expected, "{items:?}");
+                for time in 0..50 {
+                    assert_eq!(schedule.at(time), expected.at(time), "{items:?} at {time}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::vec;
+
+use sortedlist_rs::SortedList;
+
+use crate::{create_time_order_events, process_end_case, process_start_case, recreate_priority_index, Decision, Fragment, ObjHolder, Running, TempObj, TimedEvent, Timed, TpoError, Trace};
+
+/// Lazy version of [`time_order_by_priority`](crate::time_order_by_priority)
+///
+/// The time_line is still created and sorted upfront but the sweep only runs when the next
+/// item is asked, so a consumer that stops early does not pay for the rest of the time_line.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_iter, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 0, end: 24, priority: 1 },
+///         Obj { start: 24, end: 48, priority: 1 },
+///         Obj { start: 48, end: 72, priority: 1 },
+///     ];
+///
+///     //only the first day
+///     let first_day: Vec<Obj> = time_order_by_priority_iter(prograns)
+///         .take_while(|obj| obj.start < 24)
+///         .collect();
+///
+///     assert_eq!(first_day, vec![Obj { start: 0, end: 24, priority: 1 }]);
+/// ```
+pub fn time_order_by_priority_iter<T, U>(vec: Vec::<T>) -> TimeOrderIter<T, U>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    let time_line: Vec<TimedEvent<U>> = create_time_order_events(&arena);
+
+    TimeOrderIter::new(arena, time_line)
+}
+
+/// Iterator returned by [`time_order_by_priority_iter`]
+///
+/// It owns the objects and only holds positions to them, so it is `Send` when `T` and `U` are
+pub struct TimeOrderIter<T, U> {
+    arena: Vec<ObjHolder<T>>,
+    sweep: Sweep<U>,
+}
+
+impl<T, U> TimeOrderIter<T, U>
+where U: Copy
+{
+    /// `time_line` must be created from `arena`
+    pub(crate) fn new(arena: Vec<ObjHolder<T>>, time_line: Vec<TimedEvent<U>>) -> Self {
+        Self {
+            arena,
+            sweep: Sweep::new(time_line),
+        }
+    }
+
+    /// Same as `next` but the original object is not cloned
+    ///
+    /// # Panics
+    ///
+    /// If the algorithm gets into a impossible state, see [`TpoError::InvariantViolation`]
+    pub(crate) fn next_fragment(&mut self) -> Option<Fragment<U>> {
+        self.sweep.next_fragment(&self.arena)
+    }
+
+    pub(crate) fn try_next_fragment(&mut self) -> Result<Option<Fragment<U>>, TpoError> {
+        self.sweep.try_next_fragment(&self.arena)
+    }
+
+    /// The objects the fragments point to
+    pub(crate) fn arena(&self) -> &[ObjHolder<T>] {
+        &self.arena
+    }
+
+    /// Run the whole sweep and give back the objects together with all fragments
+    ///
+    /// # Panics
+    ///
+    /// Same as [`next_fragment`](TimeOrderIter::next_fragment)
+    pub(crate) fn into_fragments(self) -> (Vec<ObjHolder<T>>, Vec<Fragment<U>>) {
+        let fragments = self.sweep.into_fragments(&self.arena);
+        (self.arena, fragments)
+    }
+}
+
+/// The state of the sweep over a time_line, the objects are kept outside so many sweeps
+/// can share the same arena
+pub(crate) struct Sweep<U> {
+    //Each TimedEvent can finish at most one object, so this never holds more than one
+    result: Vec<Fragment<U>>,
+    trace: Trace<U>,
+    temp_obj: TempObj<U>,
+    running_prograns: SortedList<Running>,
+    inter: vec::IntoIter<TimedEvent<U>>,
+}
+
+impl<U> Sweep<U>
+where U: Copy
+{
+    pub(crate) fn new(time_line: Vec<TimedEvent<U>>) -> Self {
+        Self {
+            result: Vec::new(),
+            trace: None,
+            temp_obj: None,
+            running_prograns: SortedList::new(),
+            inter: time_line.into_iter(),
+        }
+    }
+
+    /// Same as `new` but every decision is recorded, see [`take_trace`](Sweep::take_trace)
+    pub(crate) fn traced(time_line: Vec<TimedEvent<U>>) -> Self {
+        Self {
+            trace: Some(Vec::new()),
+            ..Self::new(time_line)
+        }
+    }
+
+    /// The decisions recorded until now, empty if the sweep was not created with `traced`
+    pub(crate) fn take_trace(&mut self) -> Vec<Decision<U>> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// # Panics
+    ///
+    /// If the algorithm gets into a impossible state, see [`TpoError::InvariantViolation`]
+    pub(crate) fn next_fragment<T>(&mut self, arena: &[ObjHolder<T>]) -> Option<Fragment<U>> {
+        match self.try_next_fragment(arena) {
+            Ok(fragment) => fragment,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// `arena` must be the one the time_line was created from
+    pub(crate) fn try_next_fragment<T>(&mut self, arena: &[ObjHolder<T>]) -> Result<Option<Fragment<U>>, TpoError> {
+        while self.result.is_empty() {
+            let Some(item) = self.inter.next() else {
+                return Ok(None);
+            };
+            match item {
+                TimedEvent::Start { reference , time } => {
+                    process_start_case(&mut self.result, &mut self.trace, &mut self.temp_obj, &mut self.running_prograns, arena, reference, time);
+                },
+                TimedEvent::End { reference , time } => {
+                    process_end_case(&mut self.result, &mut self.trace, &mut self.temp_obj, &mut self.running_prograns, arena, &mut self.inter, reference, time)?;
+                },
+            }
+        }
+        Ok(self.result.pop())
+    }
+
+    /// # Panics
+    ///
+    /// Same as [`next_fragment`](Sweep::next_fragment)
+    pub(crate) fn into_fragments<T>(mut self, arena: &[ObjHolder<T>]) -> Vec<Fragment<U>> {
+        std::iter::from_fn(|| self.next_fragment(arena)).collect()
+    }
+}
+
+impl<T, U> Iterator for TimeOrderIter<T, U>
+where T: Timed<U> + Clone,
+      U: Ord + Copy
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_fragment().map(|fragment| fragment.into_obj(&self.arena))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use crate::{time_order_by_priority_iter, Timed};
+
+    thread_local! {
+        static CLONES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Obj {
+        start: i32,
+        end: i32,
+        priority: i32,
+    }
+
+    /// Count the clones to know how many objects the sweep produced
+    impl Clone for Obj {
+        fn clone(&self) -> Self {
+            CLONES.with(|clones| clones.set(clones.get() + 1));
+            Self { start: self.start, end: self.end, priority: self.priority }
+        }
+    }
+
+    impl Timed<i32> for Obj {
+        fn get_start(&self) -> i32 {
+            self.start
+        }
+        fn get_end(&self) -> i32 {
+            self.end
+        }
+        fn set_start(&mut self, time: i32) {
+            self.start = time;
+        }
+        fn set_end(&mut self, time: i32) {
+            self.end = time;
+        }
+    }
+
+    impl PartialOrd for Obj {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.priority.cmp(&other.priority))
+        }
+    }
+
+    #[test]
+    fn test_stop_early() {
+        let prograns: Vec<Obj> = (0..100).map(|day| Obj {
+            start: day * 24,
+            end: (day + 1) * 24,
+            priority: 1,
+        }).collect();
+
+        CLONES.with(|clones| clones.set(0));
+        let mut iter = time_order_by_priority_iter(prograns);
+
+        assert_eq!(iter.next(), Some(Obj { start: 0, end: 24, priority: 1 }));
+        assert_eq!(iter.next(), Some(Obj { start: 24, end: 48, priority: 1 }));
+
+        //only the 2 returned objects were produced
+        assert_eq!(CLONES.with(|clones| clones.get()), 2);
+
+        assert_eq!(iter.count(), 98);
+    }
+
+    #[test]
+    fn test_send_between_threads() {
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 2, end: 4, priority: 2 },
+        ];
+
+        let mut iter = time_order_by_priority_iter(prograns);
+        assert_eq!(iter.next(), Some(Obj { start: 0, end: 2, priority: 1 }));
+
+        let rest: Vec<Obj> = std::thread::spawn(move || iter.collect()).join().unwrap();
+        assert_eq!(rest, vec![
+            Obj { start: 2, end: 4, priority: 2 },
+            Obj { start: 4, end: 10, priority: 1 },
+        ]);
+    }
+}
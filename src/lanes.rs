@@ -0,0 +1,182 @@
+use sortedlist_rs::SortedList;
+
+use crate::{create_time_order_events, get_time, recreate_priority_index, running, ObjHolder, Running, TimedEvent, Timed};
+
+/// A function to order a list of Timed objects by priority keeping the `lanes` higher
+/// priorities visible at the same time
+///
+/// Each returned object is tagged with the lane it was shown on. A object keeps its lane
+/// while it is visible, so it does not change lane when other lanes change; a new visible
+/// object takes the lowest free lane.
+///
+/// All events on the same time are processed together, so no zero length segment is created.
+/// The result is sorted by start time and then by lane.
+///
+/// With a single lane the result is the same as
+/// [`time_order_by_priority_merge_adjacent`](crate::time_order_by_priority_merge_adjacent), the
+/// touching segments of the same object are shown as one.
+///
+/// # Example
+/// ```text
+/// Here the priority is represented by the height, with 2 lanes
+///                     |------ C ------|
+///             |-------------- B --------------|
+///     |---------------------- A ----------------------|
+///   12:00   12:30   13:00   13:30   14:00   14:30   15:00
+///
+/// The expected result is:
+///
+/// lane 0  |-- A --|       |------ C ------|       |-- A --|
+/// lane 1          |-------------- B --------------|
+///   12:00   12:30   13:00   13:30   14:00   14:30   15:00
+///
+/// C takes the lane of A when it starts because A is out of the 2 higher priorities
+/// and B stays on lane 1 the whole time
+/// ```
+pub fn time_order_by_priority_lanes<T, U>(vec: Vec::<T>, lanes: usize) -> Vec<(usize, T)>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    let time_line: Vec<TimedEvent<U>> = create_time_order_events(&arena);
+
+    let mut result: Vec<(usize, T)> = Vec::new();
+
+    //the object visible on each lane and when it was shown there
+    let mut visible: Vec<Option<(Running, U)>> = vec![None; lanes];
+
+    //sorted list to keep track of the keys on ordey by priorities
+    let mut running_prograns: SortedList<Running> = SortedList::new();
+
+    let mut inter = time_line.into_iter().peekable();
+
+    while let Some(item) = inter.next() {
+
+        let time = get_time(&item);
+        apply_event(&mut running_prograns, &arena, item);
+        while let Some(item) = inter.next_if(|item| get_time(item) == time) {
+            apply_event(&mut running_prograns, &arena, item);
+        }
+
+        //the lower priority that is still visible
+        let threshold = running_prograns.len().checked_sub(lanes)
+            .and_then(|index| running_prograns.get(index))
+            .or(running_prograns.first())
+            .cloned();
+
+        //finish the objects that are not on the top anymore
+        for (lane, slot) in visible.iter_mut().enumerate() {
+            let Some((current, start)) = *slot else {
+                continue;
+            };
+            let is_visible = running_prograns.binary_search(&current).is_ok()
+                && threshold.is_some_and(|threshold| current >= threshold);
+            if !is_visible {
+                let mut obj = arena[current.1].obj.clone();
+                obj.set_start(start);
+                obj.set_end(time);
+                result.push((lane, obj));
+                *slot = None;
+            }
+        }
+
+        //show the new objects on the top on the free lanes
+        let top = running_prograns.len().saturating_sub(lanes);
+        for index in top..running_prograns.len() {
+            //unwrap is safe because index < len
+            let item = running_prograns.get(index).unwrap();
+            if visible.iter().flatten().any(|(current, _)| current == item) {
+                continue;
+            }
+            //unwrap is safe because there is at most `lanes` objects on the top
+            let slot = visible.iter_mut().find(|slot| slot.is_none()).unwrap();
+            *slot = Some((*item, time));
+        }
+    }
+
+    result.sort_by(|(lane_a, a), (lane_b, b)| {
+        a.get_start().cmp(&b.get_start()).then(lane_a.cmp(lane_b))
+    });
+    result
+}
+
+fn apply_event<T, U>(running_prograns: &mut SortedList<Running>, arena: &[ObjHolder<T>], item: TimedEvent<U>) {
+    match item {
+        TimedEvent::Start { reference, time: _ } => {
+            running_prograns.insert(running(arena, reference));
+        },
+        TimedEvent::End { reference, time: _ } => {
+            if let Ok(index) = running_prograns.binary_search(&running(arena, reference)) {
+                running_prograns.remove(index);
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{time_order_by_priority_lanes, time_order_by_priority_merge_adjacent};
+    use crate::test_util::{complex_example, obj, random_objs, Obj};
+
+    #[test]
+    fn test_single_lane_same_as_merge_adjacent() {
+        let single_lane = |prograns: Vec<Obj>| -> Vec<Obj> {
+            time_order_by_priority_lanes(prograns, 1)
+                .into_iter()
+                .map(|(lane, obj)| {
+                    assert_eq!(lane, 0);
+                    obj
+                })
+                .collect()
+        };
+
+        assert_eq!(single_lane(complex_example()), time_order_by_priority_merge_adjacent(complex_example()));
+
+        //a zero length object inside another one does not split it
+        let prograns = vec![obj(21, 28, 1), obj(26, 26, 2)];
+        assert_eq!(single_lane(prograns.clone()), vec![obj(21, 28, 1)]);
+        assert_eq!(single_lane(prograns.clone()), time_order_by_priority_merge_adjacent(prograns));
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..2000 {
+            let prograns = random_objs(&mut rng, 8);
+            assert_eq!(single_lane(prograns.clone()), time_order_by_priority_merge_adjacent(prograns.clone()), "{prograns:?}");
+        }
+    }
+
+    /// The example of the documentation
+    #[test]
+    fn test_two_lanes() {
+        let prograns = vec![
+            Obj { start: 1200, end: 1500, priority: 1 },
+            Obj { start: 1230, end: 1430, priority: 2 },
+            Obj { start: 1300, end: 1400, priority: 3 },
+        ];
+
+        assert_eq!(time_order_by_priority_lanes(prograns, 2), vec![
+            (0, Obj { start: 1200, end: 1300, priority: 1 }),
+            (1, Obj { start: 1230, end: 1430, priority: 2 }),
+            (0, Obj { start: 1300, end: 1400, priority: 3 }),
+            (0, Obj { start: 1400, end: 1500, priority: 1 }),
+        ]);
+    }
+
+    /// B must stay on lane 1 even when A (lane 0) finishes and C starts
+    #[test]
+    fn test_lane_is_stable() {
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 3 },
+            Obj { start: 5, end: 20, priority: 1 },
+            Obj { start: 10, end: 15, priority: 2 },
+        ];
+
+        assert_eq!(time_order_by_priority_lanes(prograns, 2), vec![
+            (0, Obj { start: 0, end: 10, priority: 3 }),
+            (1, Obj { start: 5, end: 20, priority: 1 }),
+            (0, Obj { start: 10, end: 15, priority: 2 }),
+        ]);
+    }
+}
@@ -68,12 +68,66 @@
 //!     **After this the final result list should conteins the final list**
 
 mod test_example;
-
-use std::{cell::RefCell, fmt::Debug, rc::Rc, vec};
+#[cfg(test)]
+mod test_util;
+mod streaming;
+mod iter;
+mod lanes;
+mod filter;
+mod segment;
+mod error;
+mod normalize;
+mod tie_break;
+mod priority;
+mod scheduler;
+mod cluster;
+mod trace;
+mod coverage;
+mod schedule;
+mod incremental;
+mod dynamic;
+mod aging;
+mod round_robin;
+mod deferrable;
+mod live;
+#[cfg(feature = "rayon")]
+mod parallel;
+
+pub use streaming::StreamingScheduler;
+pub use iter::{time_order_by_priority_iter, TimeOrderIter};
+pub use lanes::time_order_by_priority_lanes;
+pub use filter::{
+    absorb_short_segments, fill_gaps, time_order_by_priority_merge_adjacent, time_order_by_priority_with_filler,
+    time_order_by_priority_with_min_length, ShortSegmentRule,
+};
+pub use segment::{resolve, time_order_by_priority_segments, BorrowedSegment, Segment};
+pub use error::TpoError;
+pub use normalize::{normalize, validate, IntervalPolicy, NormalizeReport};
+pub use tie_break::{time_order_by_priority_with_tie_break, Comparator, TieBreak};
+pub use priority::{time_order_by_priority_by, time_order_by_priority_by_key};
+pub use scheduler::{Boundary, Scheduler};
+pub use cluster::{overlap_clusters, Cluster};
+pub use trace::{time_order_by_priority_traced, Decision, DecisionKind};
+pub use coverage::{coverage_from_segments, coverage_report, Coverage, LostInterval};
+pub use schedule::{Schedule, ScheduleDiff, SegmentChange};
+pub use incremental::{IncrementalSchedule, ScheduleUpdate};
+pub use dynamic::{time_order_by_dynamic_priority, DynamicPriority};
+pub use aging::{time_order_by_priority_aging, AgedSegment, Aging};
+pub use round_robin::time_order_by_priority_round_robin;
+pub use deferrable::{time_order_by_priority_deferrable, Deferrable};
+#[cfg(feature = "rayon")]
+pub use parallel::time_order_by_priority_par;
+
+use std::fmt::Debug;
 
 use sortedlist_rs::SortedList;
+use trace::record;
 
-pub trait Timed<U> : PartialOrd
+/// A object that happens on a interval of time
+///
+/// The priority of the objects is given by `PartialOrd` on the functions that do not
+/// receive it otherwise, the higher wins
+pub trait Timed<U>
 where U: PartialOrd + Copy
 {
     fn get_start(&self) -> U;
@@ -85,91 +139,109 @@ where U: PartialOrd + Copy
 struct ObjHolder<T> {
     obj: T,
     priority: usize,
+    //position of the object on the input
+    index: usize,
 }
 
 impl<T> ObjHolder<T> {
-    fn new(data: (usize, T)) -> RefObj<T> {
-        let (mut priority, obj) = data;
+    fn new(data: (usize, (usize, T))) -> Self {
+        let (mut priority, (index, obj)) = data;
         priority += 1;
-        Rc::new(RefCell::new(Self {
+        Self {
             obj,
             priority,
-        }))
+            index,
+        }
     }
 }
 
-impl<T> Eq for ObjHolder<T>
-where T: PartialEq
-{}
+/**
+ * This is the position of a user object on the arena, the arena is a Vec<ObjHolder<T>>
+ * that owns all objects, this is to prevent unnecessary cloning of the object
+ * and keeps everything `Send` when the object is
+ */
+type Handle = usize;
 
-impl<T> Ord for ObjHolder<T>
-where T: PartialOrd
-{
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(&other).unwrap_or(std::cmp::Ordering::Less)
-    }
+/**
+ * A item of the running_prograns list, the priority comes first so the list is
+ * ordered by priority, and as the priorities are unique the handle never decides
+ */
+type Running = (usize, Handle);
+
+/// The item of the running_prograns list for the object on `reference`
+fn running<T>(arena: &[ObjHolder<T>], reference: Handle) -> Running {
+    (arena[reference].priority, reference)
 }
 
-impl<T> PartialOrd for ObjHolder<T>
-where T: PartialOrd
-{
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.priority.partial_cmp(&other.priority)
-    }
+#[derive(Debug)]
+enum TimedEvent<U> {
+    Start{time: U,reference: Handle},
+    End{time: U,reference: Handle},
 }
 
-impl<T> PartialEq for ObjHolder<T>
-where T: PartialEq
+fn get_time<U>(item: &TimedEvent<U>) -> U
+where U: Copy
 {
-    fn eq(&self, other: &Self) -> bool {
-        self.priority == other.priority
+    match item {
+        TimedEvent::Start { time, reference: _ } => *time,
+        TimedEvent::End { time, reference: _ } => *time,
     }
 }
 
-/**
- * This is a reference to a user object that is wrapped in a RC & RefCell combination
- * this is to prevent unnecessary cloning of the object
- */
-type RefObj<T> = Rc<RefCell<ObjHolder<T>>>;
-
-#[derive(Debug)]
-enum TimedEvent<T, U> {
-    Start{time: U,reference: RefObj<T>},
-    End{time: U,reference: RefObj<T>},
-}
-
 /// recreate the priority index to garantee priority uniqueness
 /// this is done by sorting the list by T::Ord and creating a new priority from the index result
-fn recreate_priority_index<T, U>(mut vec: Vec::<T>) -> Vec<RefObj<T>>
-where T: Timed<U> + Clone,
+///
+/// The result is the arena, ordered by priority
+fn recreate_priority_index<T, U>(vec: Vec::<T>) -> Vec<ObjHolder<T>>
+where T: Timed<U> + PartialOrd + Clone,
       U: Ord + Copy
 {
+    recreate_priority_index_by(vec, |(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Less))
+}
+
+/// Same as `recreate_priority_index` but sorting by `compare`, that receives the objects
+/// together with their position on the input
+///
+/// The position of each object on the input is kept on the holder, so it is possible to know
+/// from where each object came after the sort
+fn recreate_priority_index_by<T, F>(vec: Vec::<T>, compare: F) -> Vec<ObjHolder<T>>
+where F: FnMut(&(usize, T), &(usize, T)) -> std::cmp::Ordering
+{
+    let mut vec: Vec<(usize, T)> = vec.into_iter().enumerate().collect();
+
     //This sort by priority and create a new priority index to garantee priority uniqueness 
-    vec.sort_by(|a,b| a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Less));
+    vec.sort_by(compare);
 
-    //wrap the object in a RefObj<T> to prevent unnecessary cloning
+    //move the object to the arena to prevent unnecessary cloning
     vec.into_iter().enumerate().map(ObjHolder::new).collect()
 }
 
-fn create_time_order_events<T, U>(vec: Vec<RefObj<T>>) -> Vec<TimedEvent<T, U>>
+fn create_time_order_events<T, U>(arena: &[ObjHolder<T>]) -> Vec<TimedEvent<U>>
 where T: Clone + Timed<U>,
       U: Ord + Copy
+{
+    create_time_order_events_by(arena, |obj| (obj.get_start(), obj.get_end()))
+}
+
+/// Same as `create_time_order_events` but the start and end of each object is given by `span`
+fn create_time_order_events_by<T, U, F>(arena: &[ObjHolder<T>], span: F) -> Vec<TimedEvent<U>>
+where U: Ord + Copy,
+      F: Fn(&T) -> (U, U)
 {
     //Create a list of timed events ordered by time
-    let mut vec: Vec<TimedEvent<T, U>> = vec.into_iter().map(|x| {
-        let start = x.borrow().obj.get_start();
-        let end = x.borrow().obj.get_end();
+    let mut vec: Vec<TimedEvent<U>> = arena.iter().enumerate().flat_map(|(reference, x)| {
+        let (start, end) = span(&x.obj);
         vec![
             TimedEvent::Start {
                 time: start,
-                reference: x.clone(),
+                reference,
             },
             TimedEvent::End {
                 time: end,
-                reference: x,
+                reference,
             },
         ]
-    }).flatten()
+    })
     .collect();
     vec.sort_by(|a, b| {
         {match a {
@@ -183,6 +255,41 @@ where T: Clone + Timed<U>,
     vec
 }
 
+/// A finished piece of the time_line
+///
+/// It still points to the holder of the original object on the arena, so it is possible to
+/// know from where it came, and the object is only cloned when the piece is given back to the user
+#[derive(Debug, Clone, Copy)]
+struct Fragment<U> {
+    reference: Handle,
+    start: U,
+    end: U,
+}
+
+impl<U> Fragment<U>
+where U: Ord + Copy
+{
+    /// Clone the original object with the start and end of this fragment
+    fn into_obj<T>(self, arena: &[ObjHolder<T>]) -> T
+    where T: Timed<U> + Clone
+    {
+        let mut obj = arena[self.reference].obj.clone();
+        obj.set_start(self.start);
+        obj.set_end(self.end);
+        obj
+    }
+}
+
+/**
+ * The object being played right now and the time it started to be played
+ */
+type TempObj<U> = Option<(Handle, U)>;
+
+/**
+ * The decisions taken by the sweep, only recorded when it is Some
+ */
+type Trace<U> = Option<Vec<Decision<U>>>;
+
 /**
  * On case of End is very similar to Start
  * 
@@ -192,51 +299,67 @@ where T: Clone + Timed<U>,
  * finish the current object and start a new one for the top of the priority list
  * 
  * if there is no more elements on the list, get the next element from the iterator
+ * and if the iterator is also empty there is no current object anymore (idle)
+ * 
+ * fails if the next element is not a Start, this only happens with a object that
+ * finishes before it starts
  * 
  */
-fn process_end_case<T, U>(
-    result: &mut Vec<T>,
-    temp_obj: &mut RefObj<T>,
-    sorted_list: &mut SortedList<RefObj<T>>,
-    inter: &mut vec::IntoIter<TimedEvent<T, U>>,
-    reference: RefObj<T>,
-    time: U) 
-where T: Timed<U> + Clone,
-      U: Ord + Copy
+#[allow(clippy::too_many_arguments)]
+fn process_end_case<T, U, I>(
+    result: &mut Vec<Fragment<U>>,
+    trace: &mut Trace<U>,
+    temp_obj: &mut TempObj<U>,
+    sorted_list: &mut SortedList<Running>,
+    arena: &[ObjHolder<T>],
+    inter: &mut I,
+    reference: Handle,
+    time: U) -> Result<(), TpoError>
+where U: Copy,
+      I: Iterator<Item = TimedEvent<U>>
 {
-    if let Ok(index) = sorted_list.binary_search(&reference) {
+    if let Ok(index) = sorted_list.binary_search(&running(arena, reference)) {
         sorted_list.remove(index);
     }
 
-    if reference.borrow().priority >= temp_obj.borrow().priority {
+    let Some((current, start)) = temp_obj.take() else {
+        return Ok(());
+    };
 
-        temp_obj.borrow_mut().obj.set_end(time);
-        result.push(temp_obj.borrow().obj.clone());
+    if arena[reference].priority < arena[current].priority {
+        *temp_obj = Some((current, start));
+        return Ok(());
+    }
 
-        if let Some(last_item) = sorted_list.last() {
-        
-            *temp_obj = last_item.clone().into();
-            temp_obj.borrow_mut().obj.set_start(time);
-        
-        } else if let Some(item) = inter.next() {
-            //if finised the conflict and have more elements
+    result.push(Fragment { reference: current, start, end: time });
 
-            match item {
-                TimedEvent::Start { reference , time } => {
-                    *temp_obj = reference.clone();
-                    temp_obj.borrow_mut().obj.set_start(time);
-
-                    sorted_list.insert(temp_obj.clone());
-                },
-                _ => {
-                    //This panic should never happen
-                    //because to finish the conflict the last End had happened, and the only possible next is a Start
-                    //outside the conflict
-                    panic!("Error");
-                }
+    if let Some((_, last_item)) = sorted_list.last() {
+    
+        record(trace, arena, time, DecisionKind::Resumed, Some(*last_item), Some(current));
+        *temp_obj = Some((*last_item, time));
+    
+    } else if let Some(item) = inter.next() {
+        //if finised the conflict and have more elements
+        record(trace, arena, time, DecisionKind::Ended, None, Some(current));
+
+        match item {
+            TimedEvent::Start { reference , time } => {
+                sorted_list.insert(running(arena, reference));
+
+                record(trace, arena, time, DecisionKind::Started, Some(reference), None);
+                *temp_obj = Some((reference, time));
+            },
+            _ => {
+                //This should never happen
+                //because to finish the conflict the last End had happened, and the only possible next is a Start
+                //outside the conflict
+                return Err(TpoError::InvariantViolation("a End came after the running_prograns list got empty"));
             }
         }
+    } else {
+        record(trace, arena, time, DecisionKind::Ended, None, Some(current));
     }
+    Ok(())
 }
 
 
@@ -247,25 +370,33 @@ where T: Timed<U> + Clone,
  * 
  * and if the reference has a higher priority than the current object
  * finish the current object and start a new one for the reference
+ * 
+ * if there is no current object the reference simply becomes the current one
  */
 fn process_start_case<T,U>(
-    result: &mut Vec<T>, 
-    temp_obj: &mut RefObj<T>, 
-    sorted_list: &mut SortedList<RefObj<T>>, 
-    reference: RefObj<T>, 
+    result: &mut Vec<Fragment<U>>, 
+    trace: &mut Trace<U>,
+    temp_obj: &mut TempObj<U>, 
+    sorted_list: &mut SortedList<Running>, 
+    arena: &[ObjHolder<T>],
+    reference: Handle, 
     time: U)
-where T: Timed<U> + Clone,
-        U: Ord + Copy
+where U: Copy
 {
-    sorted_list.insert(reference.clone());
+    sorted_list.insert(running(arena, reference));
 
-    if reference.borrow().priority >= temp_obj.borrow().priority {
-        temp_obj.borrow_mut().obj.set_end(time);
-        result.push(temp_obj.borrow().obj.clone());
-    
-        *temp_obj = reference.clone();
-        temp_obj.borrow_mut().obj.set_start(time);
+    if let Some((current, start)) = temp_obj.take() {
+        if arena[reference].priority < arena[current].priority {
+            *temp_obj = Some((current, start));
+            return;
+        }
+        result.push(Fragment { reference: current, start, end: time });
+        record(trace, arena, time, DecisionKind::Preempted, Some(reference), Some(current));
+    } else {
+        record(trace, arena, time, DecisionKind::Started, Some(reference), None);
     }
+
+    *temp_obj = Some((reference, time));
 }
 
 
@@ -363,7 +494,7 @@ where T: Timed<U> + Clone,
 ///     assert!(obj.end == "16:00:00".parse().unwrap());
 /// ```
 pub fn time_order_by_priority<T, U>(vec: Vec::<T>) -> Vec::<T>
-where T: Timed<U> + Clone,
+where T: Timed<U> + PartialOrd + Clone,
 U: Ord + Copy
 {
 
@@ -372,51 +503,90 @@ U: Ord + Copy
         return vec;
     }
     
-    let vec: Vec<RefObj<T>> = recreate_priority_index(vec);
-
-    let time_line: Vec<TimedEvent<T, U>> = create_time_order_events(vec);
-
-    //This vector will store the final result
-    //Here is only place where the object is cloned
-    let mut result: Vec<T> = Vec::<T>::new();
-
-    let mut temp_obj: RefObj<T>;
-
-    //sorted list to keep track of the keys on ordey by priorities
-    let mut running_prograns: SortedList<RefObj<T>> = SortedList::new();
+    time_order_by_priority_iter(vec).collect()
+}
 
-    let mut inter = time_line.into_iter();
+/// Same as [`time_order_by_priority`] but a bad object gives back a error instead of a panic
+/// or a silently wrong result
+///
+/// # Errors
+///
+/// * [`TpoError::InvertedInterval`] if a object starts after it finishes, use [`normalize`] to fix them before
+/// * [`TpoError::IncomparablePriorities`] if a object can not be compared with the first one, or with
+///   itself as a `NaN`, this is checked before the sort so it does not panic on them
+/// * [`TpoError::InvariantViolation`] if the algorithm gets into a state that should be impossible
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{try_time_order_by_priority, Timed, TpoError};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: f64,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             self.priority.partial_cmp(&other.priority)
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1.0 },
+///         Obj { start: 16, end: 14, priority: 2.0 },
+///     ];
+///     assert_eq!(try_time_order_by_priority(prograns), Err(TpoError::InvertedInterval { index: 1 }));
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1.0 },
+///         Obj { start: 14, end: 16, priority: f64::NAN },
+///     ];
+///     assert!(matches!(try_time_order_by_priority(prograns), Err(TpoError::IncomparablePriorities { .. })));
+/// ```
+pub fn try_time_order_by_priority<T, U>(vec: Vec::<T>) -> Result<Vec::<T>, TpoError>
+where T: Timed<U> + PartialOrd + Clone,
+U: Ord + Copy
+{
+    validate(&vec)?;
 
-    //unwrap is safe because we have at least 4 elements on the list
-    //The algorithm only works with 2 or more Timed elements each create 2 points on the timeline
-    let item = inter.next().unwrap();
+    //trivial case not worth pass through the algorithm
+    if vec.len() <= 1 {
+        return Ok(vec);
+    }
 
-    temp_obj = match &item {
-        TimedEvent::Start { time: _, reference} => reference,
-        TimedEvent::End { time: _, reference } => reference
-    }.clone();
+    //the sort panics on a comparison that is not a total order, so the incomparable objects
+    //are found before it
+    if let Some(second) = vec.iter().position(|obj| vec[0].partial_cmp(obj).is_none()) {
+        return Err(TpoError::IncomparablePriorities { first: 0, second });
+    }
 
-    temp_obj.borrow_mut().obj.set_start(match item {
-        TimedEvent::Start { time, reference: _ } => time,
-        TimedEvent::End { time, reference: _ } => time
+    let mut incomparable: Option<(usize, usize)> = None;
+    let arena = recreate_priority_index_by(vec, |(index_a, a), (index_b, b)| {
+        a.partial_cmp(b).unwrap_or_else(|| {
+            incomparable.get_or_insert((*index_a, *index_b));
+            std::cmp::Ordering::Less
+        })
     });
+    if let Some((first, second)) = incomparable {
+        return Err(TpoError::IncomparablePriorities { first, second });
+    }
 
-    running_prograns.insert(temp_obj.clone());
-    
-
-    while let Some(item) = inter.next() {
+    let time_line: Vec<TimedEvent<U>> = create_time_order_events(&arena);
 
-        match item {
-            TimedEvent::Start { reference , time } => {
-                process_start_case(&mut result, &mut temp_obj, &mut running_prograns, reference, time); 
-            },
-            TimedEvent::End { reference , time } => {
-                process_end_case(&mut result, &mut temp_obj, &mut running_prograns, &mut inter, reference, time);
-            },
-        }
+    let mut iter = TimeOrderIter::new(arena, time_line);
+    let mut result: Vec<T> = Vec::new();
+    while let Some(fragment) = iter.try_next_fragment()? {
+        result.push(fragment.into_obj(iter.arena()));
     }
-
-    result
+    Ok(result)
 }
 
 
@@ -424,7 +594,7 @@ U: Ord + Copy
 mod test {
     use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 
-    use crate::{create_time_order_events, recreate_priority_index, Timed, TimedEvent};
+    use crate::{create_time_order_events, recreate_priority_index, try_time_order_by_priority, TimeOrderIter, Timed, TimedEvent, TpoError};
 
     #[derive(Clone, Debug, PartialEq, Eq)]
     struct Obj {
@@ -458,7 +628,7 @@ mod test {
         }
     }
 
-    fn shuffle(vec: &mut Vec<Obj>) {
+    fn shuffle(vec: &mut [Obj]) {
         let options = [
             std::cmp::Ordering::Less,
             std::cmp::Ordering::Equal,
@@ -486,27 +656,44 @@ mod test {
         let mut vec = vec.iter();
         
         let item = vec.next().unwrap();
-        assert_eq!(item.borrow().obj.priority, 10);
-        assert_eq!(item.borrow().priority, 1);
-        assert_eq!(item.borrow().obj.other, 0);
+        assert_eq!(item.obj.priority, 10);
+        assert_eq!(item.priority, 1);
+        assert_eq!(item.obj.other, 0);
         
         let item = vec.next().unwrap();
-        assert_eq!(item.borrow().obj.priority, 10);
-        assert_eq!(item.borrow().priority, 2);
-        assert_eq!(item.borrow().obj.other, 1);
+        assert_eq!(item.obj.priority, 10);
+        assert_eq!(item.priority, 2);
+        assert_eq!(item.obj.other, 1);
 
         let item = vec.next().unwrap();
-        assert_eq!(item.borrow().obj.priority, 20);
-        assert_eq!(item.borrow().priority, 3);
+        assert_eq!(item.obj.priority, 20);
+        assert_eq!(item.priority, 3);
 
         let item = vec.next().unwrap();
-        assert_eq!(item.borrow().obj.priority, 30);
-        assert_eq!(item.borrow().priority, 4);
+        assert_eq!(item.obj.priority, 30);
+        assert_eq!(item.priority, 4);
 
         let item = vec.next().unwrap();
-        assert_eq!(item.borrow().obj.priority, 40);
-        assert_eq!(item.borrow().priority, 5);
+        assert_eq!(item.obj.priority, 40);
+        assert_eq!(item.priority, 5);
+
+    }
+
+    #[test]
+    fn test_recreate_priority_index_keeps_index() {
 
+        let vec = vec![
+            Obj { start: 1, end: 2, priority: 20, other: 0 },
+            Obj { start: 1, end: 2, priority: 40, other: 0 },
+            Obj { start: 0, end: 2, priority: 10, other: 0 },
+        ];
+
+        let vec = recreate_priority_index(vec);
+
+        let index: Vec<(usize, usize)> = vec.iter()
+            .map(|item| (item.priority, item.index))
+            .collect();
+        assert_eq!(index, vec![(1, 2), (2, 0), (3, 1)]);
     }
 
     #[test]
@@ -533,21 +720,20 @@ mod test {
             },
         ];
 
-        let timed_events = create_time_order_events({
-            recreate_priority_index(vec.clone())
-        });
+        let arena = recreate_priority_index(vec.clone());
+        let timed_events = create_time_order_events(&arena);
 
-        let get_time = |item: &TimedEvent<Obj, i32>| -> i32 {
+        let get_time = |item: &TimedEvent<i32>| -> i32 {
             match item {
                 TimedEvent::Start { time, reference: _ } => *time,
                 TimedEvent::End { time, reference: _ } => *time,
             }
         };
 
-        let get_ref = |item: &TimedEvent<Obj, i32>| {
+        let get_ref = |item: &TimedEvent<i32>| {
             match item {
-                TimedEvent::Start { time: _, reference } => reference.clone(),
-                TimedEvent::End { time: _, reference } => reference.clone(),
+                TimedEvent::Start { time: _, reference } => *reference,
+                TimedEvent::End { time: _, reference } => *reference,
             }
         };
 
@@ -556,34 +742,94 @@ mod test {
 
         let item = iter.next().unwrap();
         assert_eq!(get_time(item), 1);
-        assert_eq!(&(get_ref(item).borrow().obj), &vec[0]);
+        assert_eq!(&arena[get_ref(item)].obj, &vec[0]);
 
 
         let item = iter.next().unwrap();
         assert_eq!(get_time(item), 2);
-        assert_eq!(&(get_ref(item).borrow().obj), &vec[1]);
+        assert_eq!(&arena[get_ref(item)].obj, &vec[1]);
 
 
         let item = iter.next().unwrap();
         assert_eq!(get_time(item), 3);
-        assert_eq!(&(get_ref(item).borrow().obj), &vec[2]);
+        assert_eq!(&arena[get_ref(item)].obj, &vec[2]);
 
 
         let item = iter.next().unwrap();
         assert_eq!(get_time(item), 10);
-        assert_eq!(&(get_ref(item).borrow().obj), &vec[0]);
+        assert_eq!(&arena[get_ref(item)].obj, &vec[0]);
 
 
         let item = iter.next().unwrap();
         assert_eq!(get_time(item), 20);
-        assert_eq!(&(get_ref(item).borrow().obj), &vec[1]);
+        assert_eq!(&arena[get_ref(item)].obj, &vec[1]);
 
 
         let item = iter.next().unwrap();
         assert_eq!(get_time(item), 30);
-        assert_eq!(&(get_ref(item).borrow().obj), &vec[2]);
+        assert_eq!(&arena[get_ref(item)].obj, &vec[2]);
 
         assert!(iter.next().is_none());
 
     }
-}
\ No newline at end of file
+
+    /// B finishes before it starts so after A finishes the next event is the End of B
+    #[test]
+    fn test_invariant_violation() {
+
+        let vec = vec![
+            Obj { start: 0, end: 10, priority: 1, other: 0 },
+            Obj { start: 20, end: 15, priority: 1, other: 1 },
+        ];
+
+        let arena = recreate_priority_index(vec);
+        let time_line = create_time_order_events(&arena);
+        let mut iter = TimeOrderIter::new(arena, time_line);
+
+        assert!(matches!(iter.try_next_fragment(), Err(TpoError::InvariantViolation(_))));
+    }
+
+    /// Every third priority is a NaN, enough objects for the sort to check it is a total order
+    #[test]
+    fn test_incomparable_priorities_do_not_panic() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct FloatObj {
+            start: i32,
+            end: i32,
+            priority: f64,
+        }
+
+        impl Timed<i32> for FloatObj {
+            fn get_start(&self) -> i32 {
+                self.start
+            }
+            fn get_end(&self) -> i32 {
+                self.end
+            }
+            fn set_start(&mut self, time: i32) {
+                self.start = time;
+            }
+            fn set_end(&mut self, time: i32) {
+                self.end = time;
+            }
+        }
+
+        impl PartialOrd for FloatObj {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.priority.partial_cmp(&other.priority)
+            }
+        }
+
+        let vec: Vec<FloatObj> = (0..50).map(|index| FloatObj {
+            start: index,
+            end: index + 5,
+            priority: if index % 3 == 2 { f64::NAN } else { index as f64 },
+        }).collect();
+        assert_eq!(try_time_order_by_priority(vec.clone()), Err(TpoError::IncomparablePriorities { first: 0, second: 2 }));
+
+        //a NaN on the first object can not even be compared with itself
+        let mut vec = vec;
+        vec.rotate_left(2);
+        assert_eq!(try_time_order_by_priority(vec), Err(TpoError::IncomparablePriorities { first: 0, second: 0 }));
+    }
+}
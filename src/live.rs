@@ -0,0 +1,105 @@
+use std::{iter::Peekable, vec};
+
+use sortedlist_rs::SortedList;
+
+use crate::{create_time_order_events_by, get_time, running, Boundary, Handle, ObjHolder, Running, TimedEvent, Timed};
+
+/// The sweep of the functions that may choose the winner again at any time, not only when a
+/// object starts or finishes, it keeps the running objects and the segment of the one playing
+///
+/// The Ends are processed before the Starts at the same time, so a object that finishes is never
+/// a candidate. Objects that do not start before they finish (zero length or inverted) are left out.
+pub(crate) struct LiveSweep<U> {
+    events: Peekable<vec::IntoIter<TimedEvent<U>>>,
+    running_prograns: SortedList<Running>,
+    //the object playing and since when
+    current: Option<(Handle, U)>,
+}
+
+impl<U> LiveSweep<U>
+where U: Ord + Copy
+{
+    /// `has_end` tells if a object finishes on its end, the ones that do not have to be removed
+    /// with [`LiveSweep::remove`]
+    pub fn new<T>(arena: &[ObjHolder<T>], has_end: impl Fn(&T) -> bool) -> Self
+    where T: Timed<U>
+    {
+        let mut time_line = create_time_order_events_by(arena, |obj| (obj.get_start(), obj.get_end()));
+        time_line.retain(|item| {
+            let (reference, is_end) = match item {
+                TimedEvent::Start { time: _, reference } => (*reference, false),
+                TimedEvent::End { time: _, reference } => (*reference, true),
+            };
+            let obj = &arena[reference].obj;
+            obj.get_start() < obj.get_end() && (!is_end || has_end(obj))
+        });
+        Boundary::HalfOpen.sort(&mut time_line, |_| false);
+
+        Self {
+            events: time_line.into_iter().peekable(),
+            running_prograns: SortedList::new(),
+            current: None,
+        }
+    }
+
+    /// The next time the winner may change, the next Start or End or `wake` if it comes first
+    pub fn next_time(&mut self, wake: Option<U>) -> Option<U> {
+        match (self.events.peek().map(get_time), wake) {
+            (Some(next), Some(wake)) => Some(next.min(wake)),
+            (next, wake) => next.or(wake),
+        }
+    }
+
+    /// Process the Starts and Ends at `time`, `on_start` receives the objects that start
+    pub fn advance<T>(&mut self, arena: &[ObjHolder<T>], time: U, mut on_start: impl FnMut(Handle)) {
+        while let Some(item) = self.events.next_if(|item| get_time(item) == time) {
+            match item {
+                TimedEvent::Start { time: _, reference } => {
+                    self.running_prograns.insert(running(arena, reference));
+                    on_start(reference);
+                },
+                TimedEvent::End { time: _, reference } => self.remove(arena, reference),
+            }
+        }
+    }
+
+    /// Take a object out of the running ones before its End
+    pub fn remove<T>(&mut self, arena: &[ObjHolder<T>], reference: Handle) {
+        if let Ok(index) = self.running_prograns.binary_search(&running(arena, reference)) {
+            self.running_prograns.remove(index);
+        }
+    }
+
+    pub fn is_running<T>(&self, arena: &[ObjHolder<T>], reference: Handle) -> bool {
+        self.running_prograns.contains(&running(arena, reference))
+    }
+
+    /// The running objects from the lowest to the highest priority
+    pub fn running(&self) -> impl DoubleEndedIterator<Item = Handle> + '_ {
+        (0..self.running_prograns.len())
+            .filter_map(|index| self.running_prograns.get(index))
+            .map(|(_, reference)| *reference)
+    }
+
+    pub fn playing(&self) -> Option<Handle> {
+        self.current.map(|(reference, _)| reference)
+    }
+
+    /// Make `winner` the object playing from `time` and give back the segment of the one that stops
+    pub fn play<T>(&mut self, arena: &[ObjHolder<T>], time: U, winner: Option<Handle>) -> Option<T>
+    where T: Timed<U> + Clone
+    {
+        if winner == self.playing() {
+            return None;
+        }
+
+        let finished = self.current.map(|(reference, since)| {
+            let mut obj = arena[reference].obj.clone();
+            obj.set_start(since);
+            obj.set_end(time);
+            obj
+        });
+        self.current = winner.map(|reference| (reference, time));
+        finished
+    }
+}
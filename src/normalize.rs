@@ -0,0 +1,181 @@
+use crate::{Timed, TpoError};
+
+/// What [`normalize`] does with a object that starts after it finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalPolicy {
+    /// Fail with [`TpoError::InvertedInterval`]
+    Reject,
+    /// Swap the start and the end
+    Swap,
+    /// Remove the object, the empty objects (start equals to end) are also removed
+    Drop,
+    /// Move the end to the start, leaving a empty object
+    Clamp,
+}
+
+/// What [`normalize`] changed, each list has the positions of the objects on the input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizeReport {
+    pub swapped: Vec<usize>,
+    pub dropped: Vec<usize>,
+    pub clamped: Vec<usize>,
+}
+
+impl NormalizeReport {
+    /// True if nothing was changed
+    pub fn is_empty(&self) -> bool {
+        self.swapped.is_empty() && self.dropped.is_empty() && self.clamped.is_empty()
+    }
+}
+
+/// Check that every object starts before or when it finishes
+///
+/// # Errors
+///
+/// [`TpoError::InvertedInterval`] with the first object that starts after it finishes
+pub fn validate<T, U>(vec: &[T]) -> Result<(), TpoError>
+where T: Timed<U>,
+      U: Ord + Copy
+{
+    match vec.iter().position(|obj| obj.get_start() > obj.get_end()) {
+        Some(index) => Err(TpoError::InvertedInterval { index }),
+        None => Ok(()),
+    }
+}
+
+/// Fix the objects that start after they finish following `policy`, so they can not
+/// corrupt the time_line, and report what was changed
+///
+/// # Errors
+///
+/// [`TpoError::InvertedInterval`] if the policy is [`IntervalPolicy::Reject`] and a object
+/// starts after it finishes
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{normalize, IntervalPolicy, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 16, end: 14, priority: 2 },
+///     ];
+///
+///     let (prograns, report) = normalize(prograns, IntervalPolicy::Swap).unwrap();
+///     assert_eq!(prograns[1], Obj { start: 14, end: 16, priority: 2 });
+///     assert_eq!(report.swapped, vec![1]);
+/// ```
+pub fn normalize<T, U>(vec: Vec::<T>, policy: IntervalPolicy) -> Result<(Vec<T>, NormalizeReport), TpoError>
+where T: Timed<U>,
+      U: Ord + Copy
+{
+    let mut report = NormalizeReport::default();
+    let mut result: Vec<T> = Vec::with_capacity(vec.len());
+
+    for (index, mut obj) in vec.into_iter().enumerate() {
+        let start = obj.get_start();
+        let end = obj.get_end();
+
+        if start < end {
+            result.push(obj);
+            continue;
+        }
+
+        match policy {
+            IntervalPolicy::Drop => {
+                report.dropped.push(index);
+            },
+            _ if start == end => {
+                result.push(obj);
+            },
+            IntervalPolicy::Reject => {
+                return Err(TpoError::InvertedInterval { index });
+            },
+            IntervalPolicy::Swap => {
+                obj.set_start(end);
+                obj.set_end(start);
+                report.swapped.push(index);
+                result.push(obj);
+            },
+            IntervalPolicy::Clamp => {
+                obj.set_end(start);
+                report.clamped.push(index);
+                result.push(obj);
+            },
+        }
+    }
+
+    Ok((result, report))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{normalize, validate, IntervalPolicy, NormalizeReport, TpoError};
+    use crate::test_util::Obj;
+
+    fn prograns() -> Vec<Obj> {
+        vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 20, end: 15, priority: 1 },
+            Obj { start: 30, end: 30, priority: 1 },
+        ]
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate(&prograns()), Err(TpoError::InvertedInterval { index: 1 }));
+        assert_eq!(validate(&prograns()[..1]), Ok(()));
+    }
+
+    #[test]
+    fn test_reject() {
+        assert_eq!(normalize(prograns(), IntervalPolicy::Reject), Err(TpoError::InvertedInterval { index: 1 }));
+    }
+
+    #[test]
+    fn test_swap() {
+        let (result, report) = normalize(prograns(), IntervalPolicy::Swap).unwrap();
+        assert_eq!(result[1], Obj { start: 15, end: 20, priority: 1 });
+        assert_eq!(result.len(), 3);
+        assert_eq!(report, NormalizeReport { swapped: vec![1], ..Default::default() });
+    }
+
+    #[test]
+    fn test_drop() {
+        let (result, report) = normalize(prograns(), IntervalPolicy::Drop).unwrap();
+        assert_eq!(result, vec![Obj { start: 0, end: 10, priority: 1 }]);
+        assert_eq!(report, NormalizeReport { dropped: vec![1, 2], ..Default::default() });
+    }
+
+    #[test]
+    fn test_clamp() {
+        let (result, report) = normalize(prograns(), IntervalPolicy::Clamp).unwrap();
+        assert_eq!(result[1], Obj { start: 20, end: 20, priority: 1 });
+        assert_eq!(report, NormalizeReport { clamped: vec![1], ..Default::default() });
+    }
+
+    #[test]
+    fn test_nothing_to_change() {
+        let (result, report) = normalize(prograns()[..1].to_vec(), IntervalPolicy::Reject).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(report.is_empty());
+    }
+}
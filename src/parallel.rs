@@ -0,0 +1,97 @@
+use rayon::prelude::*;
+
+use crate::{cluster::split_clusters, create_time_order_events, iter::Sweep, recreate_priority_index, ObjHolder, TimedEvent, Timed};
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but each overlap cluster
+/// is resolved on a different thread
+///
+/// A cluster finishes every time the running_prograns list gets empty, nothing before that
+/// point can change what happens after it, so the clusters are independent and the result
+/// is the concatenation of their results on time order.
+///
+/// Only available with the `rayon` feature.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority, time_order_by_priority_par, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns: Vec<Obj> = (0..1000).flat_map(|day| vec![
+///         Obj { start: day * 24, end: day * 24 + 12, priority: 1 },
+///         Obj { start: day * 24 + 6, end: day * 24 + 8, priority: 2 },
+///     ]).collect();
+///
+///     assert_eq!(time_order_by_priority_par(prograns.clone()), time_order_by_priority(prograns));
+/// ```
+pub fn time_order_by_priority_par<T, U>(vec: Vec::<T>) -> Vec<T>
+where T: Timed<U> + PartialOrd + Clone + Send + Sync,
+      U: Ord + Copy + Send + Sync
+{
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    let time_line: Vec<TimedEvent<U>> = create_time_order_events(&arena);
+
+    split_clusters(time_line)
+        .into_par_iter()
+        .map(|cluster| {
+            Sweep::new(cluster).into_fragments(&arena)
+                .into_iter()
+                .map(|fragment| fragment.into_obj(&arena))
+                .collect::<Vec<T>>()
+        })
+        .collect::<Vec<Vec<T>>>()
+        .concat()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_par};
+    use crate::test_util::Obj;
+
+    ///       |- B -|             |- D -|
+    ///   |---- A ----|  |------- C -------|  |- E -|
+    ///   0   2     4 5  6        10   12 14  15    16
+    fn prograns() -> Vec<Obj> {
+        vec![
+            Obj { start: 0, end: 5, priority: 1 },
+            Obj { start: 2, end: 4, priority: 2 },
+            Obj { start: 6, end: 14, priority: 1 },
+            Obj { start: 10, end: 12, priority: 2 },
+            Obj { start: 15, end: 16, priority: 1 },
+        ]
+    }
+
+    #[test]
+    fn test_same_result_as_sequential() {
+        assert_eq!(time_order_by_priority_par(prograns()), time_order_by_priority(prograns()));
+    }
+
+    /// A object that finishes when the next one starts keeps both on the same cluster
+    #[test]
+    fn test_touching_objects() {
+        let prograns = vec![
+            Obj { start: 0, end: 5, priority: 2 },
+            Obj { start: 5, end: 10, priority: 1 },
+            Obj { start: 5, end: 5, priority: 3 },
+        ];
+        assert_eq!(time_order_by_priority_par(prograns.clone()), time_order_by_priority(prograns));
+    }
+}
@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+
+use crate::{create_time_order_events, recreate_priority_index_by, Timed, TimeOrderIter};
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the priority is
+/// given by `key` instead of `PartialOrd` on `T`, the higher key wins
+///
+/// On a tie the object that comes last on the input wins.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_by_key, Timed};
+///
+///     //no PartialOrd needed
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///     ];
+///
+///     let ordered = time_order_by_priority_by_key(prograns, |obj| obj.priority);
+///     assert_eq!(ordered, vec![
+///         Obj { start: 12, end: 14, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///     ]);
+/// ```
+pub fn time_order_by_priority_by_key<T, U, K, F>(vec: Vec::<T>, key: F) -> Vec<T>
+where T: Timed<U> + Clone,
+      U: Ord + Copy,
+      K: Ord,
+      F: Fn(&T) -> K
+{
+    time_order_by_priority_by(vec, |a, b| key(a).cmp(&key(b)))
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the priority is
+/// given by `compare` instead of `PartialOrd` on `T`, the `Greater` wins
+///
+/// On a tie the object that comes last on the input wins.
+pub fn time_order_by_priority_by<T, U, F>(vec: Vec::<T>, compare: F) -> Vec<T>
+where T: Timed<U> + Clone,
+      U: Ord + Copy,
+      F: Fn(&T, &T) -> Ordering
+{
+    let arena = recreate_priority_index_by(vec, |(_, a), (_, b)| compare(a, b));
+
+    let time_line = create_time_order_events(&arena);
+
+    TimeOrderIter::new(arena, time_line).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority_by, time_order_by_priority_by_key, Timed};
+
+    /// The natural order of this object is by name, that has nothing to do with the priority
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Booking {
+        name: char,
+        start: i32,
+        end: i32,
+        priority: i32,
+    }
+
+    impl Timed<i32> for Booking {
+        fn get_start(&self) -> i32 {
+            self.start
+        }
+        fn get_end(&self) -> i32 {
+            self.end
+        }
+        fn set_start(&mut self, time: i32) {
+            self.start = time;
+        }
+        fn set_end(&mut self, time: i32) {
+            self.end = time;
+        }
+    }
+
+    ///       |-- B --|
+    ///   |------ A ------|
+    ///   0   2       6   8
+    fn bookings() -> Vec<Booking> {
+        vec![
+            Booking { name: 'B', start: 2, end: 6, priority: 1 },
+            Booking { name: 'A', start: 0, end: 8, priority: 2 },
+        ]
+    }
+
+    #[test]
+    fn test_by_key() {
+        let ordered = time_order_by_priority_by_key(bookings(), |booking| booking.priority);
+        assert_eq!(ordered, vec![Booking { name: 'A', start: 0, end: 8, priority: 2 }]);
+    }
+
+    #[test]
+    fn test_by() {
+        //lower priority number wins
+        let ordered = time_order_by_priority_by(bookings(), |a, b| b.priority.cmp(&a.priority));
+        assert_eq!(ordered, vec![
+            Booking { name: 'A', start: 0, end: 2, priority: 2 },
+            Booking { name: 'B', start: 2, end: 6, priority: 1 },
+            Booking { name: 'A', start: 6, end: 8, priority: 2 },
+        ]);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{cmp::Ordering, ops::Add};
+
+use crate::{live::LiveSweep, recreate_priority_index, Handle, ObjHolder, Timed};
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the objects with the same
+/// priority share the time, each one plays for `quantum` and gives the turn to the next one
+///
+/// The turn goes to the object of the highest priority class that waits the longest. The quantum
+/// counts from when a object takes the turn, also while it is alone on its class, and a object cut
+/// by a higher priority loses the rest of its turn. Zero length and inverted objects are never played.
+///
+/// # Panics
+///
+/// If `quantum` is not greater than zero (the `Default` of `D`)
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_round_robin, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 0, end: 30, priority: 1 },
+///         Obj { start: 0, end: 30, priority: 1 },
+///     ];
+///
+///     let ordered = time_order_by_priority_round_robin(prograns, 10);
+///     assert_eq!(ordered.iter().map(|obj| (obj.start, obj.end)).collect::<Vec<_>>(), vec![
+///         (0, 10),
+///         (10, 20),
+///         (20, 30),
+///     ]);
+/// ```
+pub fn time_order_by_priority_round_robin<T, U, D>(vec: Vec::<T>, quantum: D) -> Vec<T>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy + Add<D, Output = U>,
+      D: Ord + Copy + Default
+{
+    assert!(quantum > D::default(), "the quantum must be greater than zero");
+
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    //the lower the turn the longer the object waits
+    let mut turns: Vec<usize> = vec![0; arena.len()];
+    let mut next_turn: usize = 0;
+
+    let mut result: Vec<T> = Vec::new();
+    let mut sweep = LiveSweep::new(&arena, |_| true);
+    //the end of the quantum of the object playing
+    let mut quantum_end: Option<U> = None;
+    let mut next = sweep.next_time(None);
+
+    while let Some(time) = next {
+        sweep.advance(&arena, time, |reference| {
+            turns[reference] = next_turn;
+            next_turn += 1;
+        });
+
+        //the running objects with the same priority of the highest one, they are together on
+        //the top of the list as the priority index follows the priority
+        let top = sweep.running().next_back();
+        let class: Vec<Handle> = sweep.running().rev()
+            .take_while(|reference| top.is_some_and(|top| arena[*reference].obj.partial_cmp(&arena[top].obj) == Some(Ordering::Equal)))
+            .collect();
+
+        let playing = sweep.playing();
+        let keep = playing.is_some_and(|reference| {
+            class.contains(&reference) && quantum_end.is_some_and(|end| time < end)
+        });
+
+        let winner = if keep {
+            playing
+        } else {
+            //the turn is over, the object playing goes to the end of the queue if it is still running
+            if let Some(reference) = playing.filter(|reference| sweep.is_running(&arena, *reference)) {
+                turns[reference] = next_turn;
+                next_turn += 1;
+            }
+            class.iter().copied().min_by_key(|reference| turns[*reference])
+        };
+
+        result.extend(sweep.play(&arena, time, winner));
+        if !keep {
+            quantum_end = winner.map(|_| time + quantum);
+        }
+
+        //the quantum only matters when there is another object on the class to take the turn
+        next = sweep.next_time(quantum_end.filter(|_| class.len() > 1));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_round_robin};
+    use crate::test_util::{complex_example, obj};
+
+    /// With unique priorities on every overlap there is nothing to share
+    #[test]
+    fn test_unique_priorities_same_as_batch() {
+        assert_eq!(time_order_by_priority_round_robin(complex_example(), 10), time_order_by_priority(complex_example()));
+    }
+
+    ///   |----------------- A -----------------|
+    ///             |------ B ------|
+    ///                   |- C -|                   higher priority
+    ///   0         10    15    25  30          50
+    ///
+    /// The quantum of A is over when B starts, so B takes the turn. C cuts B and,
+    /// once C finishes, A has the turn as it waits since 10
+    #[test]
+    fn test_round_robin() {
+        let prograns = vec![
+            obj(0, 50, 1),
+            obj(10, 30, 1),
+            obj(15, 25, 2),
+        ];
+
+        assert_eq!(time_order_by_priority_round_robin(prograns, 10), vec![
+            obj(0, 10, 1),
+            obj(10, 15, 1),
+            obj(15, 25, 2),
+            obj(25, 50, 1),
+        ]);
+    }
+
+    /// A lower priority between two equal ones on the input does not split their class
+    #[test]
+    fn test_tie_with_lower_priority_between() {
+        let prograns = vec![
+            obj(0, 20, 2),
+            obj(0, 20, 1),
+            obj(0, 20, 2),
+        ];
+
+        assert_eq!(time_order_by_priority_round_robin(prograns, 5), vec![
+            obj(0, 5, 2),
+            obj(5, 10, 2),
+            obj(10, 15, 2),
+            obj(15, 20, 2),
+        ]);
+    }
+
+    /// A object that starts when the other finishes takes a new quantum without a zero length segment
+    #[test]
+    fn test_touching() {
+        let prograns = vec![
+            obj(0, 7, 1),
+            obj(7, 20, 1),
+        ];
+
+        assert_eq!(time_order_by_priority_round_robin(prograns, 5), vec![
+            obj(0, 7, 1),
+            obj(7, 20, 1),
+        ]);
+    }
+
+    /// Zero length and inverted objects are never played and never take a turn
+    #[test]
+    fn test_zero_length_and_inverted() {
+        let prograns = vec![
+            obj(0, 10, 1),
+            obj(5, 5, 1),
+            obj(8, 2, 1),
+        ];
+
+        assert_eq!(time_order_by_priority_round_robin(prograns, 2), vec![
+            obj(0, 10, 1),
+        ]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_quantum() {
+        time_order_by_priority_round_robin(vec![obj(0, 10, 1)], 0);
+    }
+}
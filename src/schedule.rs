@@ -0,0 +1,407 @@
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{time_order_by_priority, Timed, TpoError};
+
+/// A resolved schedule, the segments are always sorted and non overlapping
+///
+/// The segments are `[start, end)`, so at the time one finishes and the next starts only the
+/// next one is playing, and a zero length segment is never playing.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{Schedule, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let schedule = Schedule::resolve(vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///         Obj { start: 18, end: 20, priority: 1 },
+///     ]);
+///
+///     assert_eq!(schedule.at(14), Some(&Obj { start: 14, end: 16, priority: 2 }));
+///     assert_eq!(schedule.at(17), None);
+///     assert_eq!(schedule.range(15..19).len(), 2);
+///     assert_eq!(schedule.first(), Some(&Obj { start: 12, end: 14, priority: 1 }));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule<T, U> {
+    segments: Vec<T>,
+    time: PhantomData<U>,
+}
+
+impl<T, U> Schedule<T, U>
+where T: Timed<U>,
+      U: Ord + Copy
+{
+    /// Resolve the objects with [`time_order_by_priority`]
+    pub fn resolve(vec: Vec::<T>) -> Self
+    where T: PartialOrd + Clone
+    {
+        Self::from_sorted(time_order_by_priority(vec))
+    }
+
+    /// Wrap segments that were already resolved
+    ///
+    /// # Errors
+    ///
+    /// * [`TpoError::InvertedInterval`] if a segment starts after it finishes
+    /// * [`TpoError::OverlappingSegments`] if a segment starts before the previous one finishes
+    pub fn try_from_segments(segments: Vec<T>) -> Result<Self, TpoError> {
+        if let Some(index) = segments.iter().position(|segment| segment.get_start() > segment.get_end()) {
+            return Err(TpoError::InvertedInterval { index });
+        }
+        if let Some(index) = segments.windows(2).position(|pair| pair[1].get_start() < pair[0].get_end()) {
+            return Err(TpoError::OverlappingSegments { index: index + 1 });
+        }
+
+        Ok(Self::from_sorted(segments))
+    }
+
+    /// Wrap segments known to be sorted and non overlapping
+    pub(crate) fn from_sorted(segments: Vec<T>) -> Self {
+        Self {
+            segments,
+            time: PhantomData,
+        }
+    }
+
+    /// The segment playing at `time`
+    pub fn at(&self, time: U) -> Option<&T> {
+        let index = self.segments.partition_point(|segment| segment.get_end() <= time);
+        self.segments.get(index).filter(|segment| segment.get_start() <= time)
+    }
+
+    /// The segments playing at some time of `range`
+    pub fn range(&self, range: Range<U>) -> &[T] {
+        let first = self.segments.partition_point(|segment| segment.get_end() <= range.start);
+        let last = self.segments.partition_point(|segment| segment.get_start() < range.end);
+        &self.segments[first..last.max(first)]
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.segments.first()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.segments.last()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.segments.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.segments
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.segments
+    }
+
+    /// What changed on air from `old` to `new`
+    ///
+    /// Two segments are of the same object when they are equal after giving them the same
+    /// start and end. A segment of `old` is matched with a segment of the same object on `new`
+    /// that overlaps it, if both have the same start and end nothing changed, otherwise it was
+    /// moved or trimmed. The segments without a match were removed (on `old`) or added (on `new`),
+    /// this includes a segment that moved to a time that does not overlap its old place.
+    ///
+    /// The zero length segments are never playing so they are ignored.
+    ///
+    /// ```rust
+    ///     use time_priority_order_algoritmh::{Schedule, SegmentChange, Timed};
+    ///
+    ///     #[derive(Debug, Clone, PartialEq)]
+    ///     struct Obj {
+    ///         start: i32,
+    ///         end: i32,
+    ///         priority: i32,
+    ///     }
+    ///
+    ///     impl Timed<i32> for Obj {
+    ///         fn get_start(&self) -> i32 { self.start }
+    ///         fn get_end(&self) -> i32 { self.end }
+    ///         fn set_start(&mut self, time: i32) { self.start = time; }
+    ///         fn set_end(&mut self, time: i32) { self.end = time; }
+    ///     }
+    ///
+    ///     impl PartialOrd for Obj {
+    ///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    ///             Some(self.priority.cmp(&other.priority))
+    ///         }
+    ///     }
+    ///
+    ///     let old = Schedule::resolve(vec![
+    ///         Obj { start: 12, end: 15, priority: 1 },
+    ///     ]);
+    ///     let new = Schedule::resolve(vec![
+    ///         Obj { start: 12, end: 15, priority: 1 },
+    ///         Obj { start: 14, end: 16, priority: 2 },
+    ///     ]);
+    ///
+    ///     let diff = Schedule::diff(&old, &new);
+    ///     assert_eq!(diff.added, vec![&Obj { start: 14, end: 16, priority: 2 }]);
+    ///     assert!(diff.removed.is_empty());
+    ///     assert_eq!(diff.changed, vec![SegmentChange {
+    ///         old: &Obj { start: 12, end: 15, priority: 1 },
+    ///         new: &Obj { start: 12, end: 14, priority: 1 },
+    ///     }]);
+    /// ```
+    pub fn diff<'a>(old: &'a Self, new: &'a Self) -> ScheduleDiff<'a, T>
+    where T: PartialEq + Clone
+    {
+        let mut diff = ScheduleDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        let mut matched = vec![false; new.segments.len()];
+
+        for segment in old.segments.iter().filter(|segment| is_playing(*segment)) {
+            let first = new.segments.partition_point(|other| other.get_end() <= segment.get_start());
+            let last = new.segments.partition_point(|other| other.get_start() < segment.get_end());
+
+            //the segments of the same object on new that overlap this one
+            let mut candidates = (first..last.max(first))
+                .filter(|index| !matched[*index] && is_playing(&new.segments[*index]))
+                .filter(|index| is_same_object(segment, &new.segments[*index]))
+                .peekable();
+
+            let unchanged = candidates.clone().find(|index| {
+                let other = &new.segments[*index];
+                other.get_start() == segment.get_start() && other.get_end() == segment.get_end()
+            });
+
+            match unchanged.or_else(|| candidates.peek().copied()) {
+                Some(index) => {
+                    matched[index] = true;
+                    if unchanged.is_none() {
+                        diff.changed.push(SegmentChange { old: segment, new: &new.segments[index] });
+                    }
+                },
+                None => diff.removed.push(segment),
+            }
+        }
+
+        diff.added = new.segments.iter().zip(matched)
+            .filter(|(segment, matched)| !matched && is_playing(*segment))
+            .map(|(segment, _)| segment)
+            .collect();
+
+        diff
+    }
+}
+
+fn is_playing<T, U>(segment: &T) -> bool
+where T: Timed<U>,
+      U: Ord + Copy
+{
+    segment.get_start() < segment.get_end()
+}
+
+/// Equal ignoring the start and the end
+fn is_same_object<T, U>(a: &T, b: &T) -> bool
+where T: Timed<U> + PartialEq + Clone,
+      U: Ord + Copy
+{
+    let mut b = b.clone();
+    b.set_start(a.get_start());
+    b.set_end(a.get_end());
+    *a == b
+}
+
+/// A segment that is on both schedules with a different start or end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentChange<'a, T> {
+    pub old: &'a T,
+    pub new: &'a T,
+}
+
+/// What changed from a schedule to another, see [`Schedule::diff`]
+///
+/// Every list is sorted by time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleDiff<'a, T> {
+    /// Segments only on the new schedule
+    pub added: Vec<&'a T>,
+    /// Segments only on the old schedule
+    pub removed: Vec<&'a T>,
+    /// Segments moved or trimmed
+    pub changed: Vec<SegmentChange<'a, T>>,
+}
+
+impl<T> ScheduleDiff<'_, T> {
+    /// True if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<'a, T, U> IntoIterator for &'a Schedule<T, U> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.iter()
+    }
+}
+
+impl<T, U> IntoIterator for Schedule<T, U> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, Schedule, SegmentChange, TpoError};
+    use crate::test_util::Obj;
+
+    ///   |--- A ---|            |---- A ----|
+    ///             |-- B --|
+    ///             |                             |-- C --|
+    ///   0        10      15   20           30   35      40
+    ///
+    /// B and a zero length A start at 10
+    fn schedule() -> Schedule<Obj, i32> {
+        Schedule::try_from_segments(vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 10, end: 10, priority: 1 },
+            Obj { start: 10, end: 15, priority: 2 },
+            Obj { start: 20, end: 30, priority: 1 },
+            Obj { start: 35, end: 40, priority: 3 },
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_at() {
+        let schedule = schedule();
+        assert_eq!(schedule.at(-1), None);
+        assert_eq!(schedule.at(0).unwrap().start, 0);
+        assert_eq!(schedule.at(9).unwrap().start, 0);
+        assert_eq!(schedule.at(10), Some(&Obj { start: 10, end: 15, priority: 2 }));
+        assert_eq!(schedule.at(17), None);
+        assert_eq!(schedule.at(30), None);
+        assert_eq!(schedule.at(39).unwrap().start, 35);
+        assert_eq!(schedule.at(40), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let schedule = schedule();
+        let starts = |segments: &[Obj]| segments.iter().map(|segment| segment.start).collect::<Vec<i32>>();
+
+        assert_eq!(starts(schedule.range(0..10)), vec![0]);
+        assert_eq!(starts(schedule.range(5..21)), vec![0, 10, 10, 20]);
+        assert_eq!(starts(schedule.range(15..20)), Vec::<i32>::new());
+        assert_eq!(starts(schedule.range(30..100)), vec![35]);
+        assert_eq!(starts(schedule.range(std::ops::Range { start: 20, end: 10 })), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_try_from_segments() {
+        let overlapping = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 5, end: 15, priority: 1 },
+        ];
+        assert_eq!(Schedule::try_from_segments(overlapping), Err(TpoError::OverlappingSegments { index: 1 }));
+
+        let inverted = vec![Obj { start: 10, end: 0, priority: 1 }];
+        assert_eq!(Schedule::try_from_segments(inverted), Err(TpoError::InvertedInterval { index: 0 }));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 2, end: 4, priority: 2 },
+        ];
+
+        let schedule = Schedule::resolve(prograns.clone());
+        assert_eq!(schedule.len(), 3);
+        assert_eq!(schedule.last(), Some(&Obj { start: 4, end: 10, priority: 1 }));
+        assert_eq!(schedule.into_vec(), time_order_by_priority(prograns));
+    }
+
+    #[test]
+    fn test_diff_same_schedule() {
+        assert!(Schedule::diff(&schedule(), &schedule()).is_empty());
+    }
+
+    ///   old  |------ A ------|     |-- C --|
+    ///   new  |-- A --|-- B --|              |-- C --|
+    ///        0       5      10     15     20       25
+    #[test]
+    fn test_diff() {
+        let old = Schedule::resolve(vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 15, end: 20, priority: 3 },
+        ]);
+        let new = Schedule::resolve(vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 5, end: 10, priority: 2 },
+            Obj { start: 20, end: 25, priority: 3 },
+        ]);
+
+        let diff = Schedule::diff(&old, &new);
+        assert_eq!(diff.changed, vec![SegmentChange {
+            old: &Obj { start: 0, end: 10, priority: 1 },
+            new: &Obj { start: 0, end: 5, priority: 1 },
+        }]);
+        assert_eq!(diff.removed, vec![&Obj { start: 15, end: 20, priority: 3 }]);
+        assert_eq!(diff.added, vec![
+            &Obj { start: 5, end: 10, priority: 2 },
+            &Obj { start: 20, end: 25, priority: 3 },
+        ]);
+    }
+
+    /// A object split in 2 keeps the first part as a change and the second as added
+    #[test]
+    fn test_diff_split() {
+        let old = Schedule::resolve(vec![Obj { start: 0, end: 10, priority: 1 }]);
+        let new = Schedule::resolve(vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 4, end: 6, priority: 2 },
+        ]);
+
+        let diff = Schedule::diff(&old, &new);
+        assert_eq!(diff.changed, vec![SegmentChange {
+            old: &Obj { start: 0, end: 10, priority: 1 },
+            new: &Obj { start: 0, end: 4, priority: 1 },
+        }]);
+        assert_eq!(diff.added, vec![
+            &Obj { start: 4, end: 6, priority: 2 },
+            &Obj { start: 6, end: 10, priority: 1 },
+        ]);
+        assert!(diff.removed.is_empty());
+    }
+}
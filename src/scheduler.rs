@@ -0,0 +1,335 @@
+use std::ops::Sub;
+
+use crate::{
+    create_time_order_events_by, filter::{absorb_short_segments_by, merge_adjacent_fragments}, fill_gaps,
+    recreate_priority_index_by, Fragment, Handle, ObjHolder, ShortSegmentRule, TieBreak, TimeOrderIter, Timed, TimedEvent,
+};
+
+/// What happens when a object finishes at the same time another one starts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// The Start and End at the same time are processed in priority order (lowest first),
+    /// this is what [`time_order_by_priority`](crate::time_order_by_priority) does
+    #[default]
+    Priority,
+    /// The objects are `[start, end)`, every End at a time is processed before the Starts
+    /// at the same time, so objects that only touch never compete and a object that only plays
+    /// between the two gives no segment. Zero length objects start and finish between the two
+    HalfOpen,
+    /// The objects are `[start, end]`, every Start at a time is processed before the Ends
+    /// at the same time, so objects that touch compete on that instant
+    Closed,
+}
+
+impl Boundary {
+    /// Reorder the events that happen at the same time, `time_line` must be sorted by time
+    ///
+    /// `is_instant` tells if the object on a handle is zero length, its Start and End are kept
+    /// together between the Ends and the Starts, as its End can not come before its Start
+    pub(crate) fn sort<U>(&self, time_line: &mut [TimedEvent<U>], is_instant: impl Fn(Handle) -> bool)
+    where U: Ord + Copy
+    {
+        let key = |event: &TimedEvent<U>| match (self, event) {
+            (Boundary::HalfOpen, TimedEvent::Start { time, reference }) => (*time, if is_instant(*reference) { 1 } else { 2 }),
+            (Boundary::HalfOpen, TimedEvent::End { time, reference }) => (*time, if is_instant(*reference) { 1 } else { 0 }),
+            (_, TimedEvent::Start { time, reference: _ }) => (*time, 0),
+            (_, TimedEvent::End { time, reference: _ }) => (*time, 1),
+        };
+
+        if *self != Boundary::Priority {
+            time_line.sort_by_key(key);
+        }
+    }
+}
+
+/// Tells if a segment from `start` to `end` is too short
+type IsShort<U> = Box<dyn Fn(U, U) -> bool>;
+
+/// Creates the object placed on a idle span from `start` to `end`
+type Filler<T, U> = Box<dyn Fn(U, U) -> T>;
+
+/// A [`time_order_by_priority`](crate::time_order_by_priority) with options
+///
+/// Every option is off by default, so a `Scheduler::new()` gives the same result as
+/// [`time_order_by_priority`](crate::time_order_by_priority).
+/// The options are applied in this order: tie break and boundary during the sweep, then
+/// merge adjacent, then min length and at last the filler.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{Scheduler, ShortSegmentRule, TieBreak, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let scheduler = Scheduler::new()
+///         .tie_break(TieBreak::FirstStarted)
+///         .min_length(2, ShortSegmentRule::ExtendPrevious)
+///         .filler(|start, end| Obj { start, end, priority: 0 });
+///
+///     let prograns = vec![
+///         Obj { start: 10, end: 15, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 1 },
+///         Obj { start: 18, end: 20, priority: 1 },
+///     ];
+///
+///     //A started first so B only plays from 15 to 16, that is too short and is absorbed by A
+///     assert_eq!(scheduler.run(prograns), vec![
+///         Obj { start: 10, end: 16, priority: 1 },
+///         Obj { start: 16, end: 18, priority: 0 },
+///         Obj { start: 18, end: 20, priority: 1 },
+///     ]);
+/// ```
+pub struct Scheduler<T, U> {
+    tie_break: TieBreak<T>,
+    boundary: Boundary,
+    min_length: Option<(IsShort<U>, ShortSegmentRule)>,
+    filler: Option<Filler<T, U>>,
+    merge_adjacent: bool,
+}
+
+impl<T, U> Default for Scheduler<T, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U> Scheduler<T, U> {
+    pub fn new() -> Self {
+        Self {
+            tie_break: TieBreak::default(),
+            boundary: Boundary::default(),
+            min_length: None,
+            filler: None,
+            merge_adjacent: false,
+        }
+    }
+
+    /// Which object wins when 2 objects have the same priority, see [`TieBreak`]
+    pub fn tie_break(mut self, tie_break: TieBreak<T>) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// What happens when a object finishes at the same time another one starts, see [`Boundary`]
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Absorb the segments shorter than `min_length`, see [`absorb_short_segments`](crate::absorb_short_segments)
+    pub fn min_length<D>(mut self, min_length: D, rule: ShortSegmentRule) -> Self
+    where U: Sub<Output = D>,
+          D: Ord + 'static
+    {
+        self.min_length = Some((Box::new(move |start, end| end - start < min_length), rule));
+        self
+    }
+
+    /// Fill the idle spans between segments, see [`fill_gaps`]
+    pub fn filler<F>(mut self, filler: F) -> Self
+    where F: Fn(U, U) -> T + 'static
+    {
+        self.filler = Some(Box::new(filler));
+        self
+    }
+
+    /// Merge the touching segments of the same original object and remove the zero length
+    /// ones, see [`time_order_by_priority_merge_adjacent`](crate::time_order_by_priority_merge_adjacent)
+    pub fn merge_adjacent(mut self, merge_adjacent: bool) -> Self {
+        self.merge_adjacent = merge_adjacent;
+        self
+    }
+}
+
+impl<T, U> Scheduler<T, U>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    /// Resolve the objects with the options of this scheduler
+    pub fn run(&self, vec: Vec::<T>) -> Vec<T> {
+        let (arena, fragments) = self.fragments(vec);
+        let segments = fragments.into_iter().map(|fragment| fragment.into_obj(&arena)).collect();
+        self.post_process(segments)
+    }
+
+    /// Same as [`run`](Scheduler::run) for objects that come from a iterator
+    pub fn run_iter<I>(&self, iter: I) -> Vec<T>
+    where I: IntoIterator<Item = T>
+    {
+        self.run(iter.into_iter().collect())
+    }
+
+    /// Same as [`run`](Scheduler::run) for read only objects, only the segments on the
+    /// result are cloned
+    pub fn run_slice(&self, vec: &[T]) -> Vec<T> {
+        let (arena, fragments) = self.fragments(vec.iter().collect());
+        let segments = fragments.into_iter().map(|fragment| {
+            let mut obj = arena[fragment.reference].obj.clone();
+            obj.set_start(fragment.start);
+            obj.set_end(fragment.end);
+            obj
+        }).collect();
+        self.post_process(segments)
+    }
+
+    /// Run the sweep with the tie break and the boundary of this scheduler, `X` is the object
+    /// or a reference to it
+    fn fragments<X>(&self, vec: Vec::<X>) -> (Vec<ObjHolder<X>>, Vec<Fragment<U>>)
+    where X: std::borrow::Borrow<T>
+    {
+        let arena = recreate_priority_index_by(vec, |(index_a, a), (index_b, b)| {
+            self.tie_break.compare_priority((*index_a, a.borrow()), (*index_b, b.borrow()))
+        });
+
+        let mut time_line = create_time_order_events_by(&arena, |obj: &X| {
+            (obj.borrow().get_start(), obj.borrow().get_end())
+        });
+        self.boundary.sort(&mut time_line, |reference| {
+            let obj = arena[reference].obj.borrow();
+            obj.get_start() == obj.get_end()
+        });
+
+        let (arena, mut fragments) = TimeOrderIter::new(arena, time_line).into_fragments();
+
+        //a object that resumes on a End and is cut by a Start at the same time does not play
+        if self.boundary == Boundary::HalfOpen {
+            fragments.retain(|fragment| {
+                let obj = arena[fragment.reference].obj.borrow();
+                fragment.start < fragment.end || obj.get_start() == obj.get_end()
+            });
+        }
+
+        if self.merge_adjacent {
+            (arena, merge_adjacent_fragments(fragments))
+        } else {
+            (arena, fragments)
+        }
+    }
+
+    fn post_process(&self, mut segments: Vec<T>) -> Vec<T> {
+        if let Some((is_short, rule)) = &self.min_length {
+            segments = absorb_short_segments_by(segments, is_short, *rule);
+        }
+        if let Some(filler) = &self.filler {
+            segments = fill_gaps(segments, filler);
+        }
+        segments
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_merge_adjacent, Boundary, Scheduler, TieBreak};
+    use crate::test_util::Obj;
+
+    ///                   |-- B --|
+    ///           |-- A --|
+    ///   |-------------- C --------------|
+    ///   0       5      10      15      20
+    fn prograns() -> Vec<Obj> {
+        vec![
+            Obj { start: 0, end: 20, priority: 1 },
+            Obj { start: 5, end: 10, priority: 2 },
+            Obj { start: 10, end: 15, priority: 3 },
+        ]
+    }
+
+    #[test]
+    fn test_default_is_time_order_by_priority() {
+        let scheduler = Scheduler::new();
+        assert_eq!(scheduler.run(prograns()), time_order_by_priority(prograns()));
+        assert_eq!(scheduler.run_iter(prograns()), time_order_by_priority(prograns()));
+        assert_eq!(scheduler.run_slice(&prograns()), time_order_by_priority(prograns()));
+    }
+
+    #[test]
+    fn test_boundary() {
+        //A finishes before B starts, C is between the two but does not play
+        let half_open = Scheduler::new().boundary(Boundary::HalfOpen).run(prograns());
+        assert_eq!(half_open, vec![
+            Obj { start: 0, end: 5, priority: 1 },
+            Obj { start: 5, end: 10, priority: 2 },
+            Obj { start: 10, end: 15, priority: 3 },
+            Obj { start: 15, end: 20, priority: 1 },
+        ]);
+
+        //B starts before A finishes, so B takes the place of A directly
+        let closed = Scheduler::new().boundary(Boundary::Closed).run(prograns());
+        assert_eq!(closed, vec![
+            Obj { start: 0, end: 5, priority: 1 },
+            Obj { start: 5, end: 10, priority: 2 },
+            Obj { start: 10, end: 15, priority: 3 },
+            Obj { start: 15, end: 20, priority: 1 },
+        ]);
+    }
+
+    /// A zero length object must still start before it finishes
+    #[test]
+    fn test_half_open_zero_length() {
+        let half_open = Scheduler::new().boundary(Boundary::HalfOpen);
+
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 5, end: 5, priority: 9 },
+            Obj { start: 20, end: 30, priority: 1 },
+        ];
+        assert_eq!(half_open.run(prograns.clone()), time_order_by_priority(prograns));
+
+        let prograns = vec![
+            Obj { start: 5, end: 5, priority: 9 },
+            Obj { start: 20, end: 30, priority: 1 },
+        ];
+        assert_eq!(half_open.run(prograns.clone()), prograns);
+
+        //the zero length object is between the end of the first and the start of the last one
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 10, end: 10, priority: 9 },
+            Obj { start: 20, end: 30, priority: 1 },
+        ];
+        assert_eq!(half_open.run(prograns.clone()), prograns);
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 5, end: 5, priority: 2 },
+        ];
+
+        let scheduler = Scheduler::new().merge_adjacent(true);
+        assert_eq!(scheduler.run_slice(&prograns), time_order_by_priority_merge_adjacent(prograns));
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let prograns = vec![
+            Obj { start: 2, end: 8, priority: 1 },
+            Obj { start: 0, end: 10, priority: 1 },
+        ];
+
+        let scheduler = Scheduler::new().tie_break(TieBreak::LatestStarted);
+        assert_eq!(scheduler.run(prograns), vec![
+            Obj { start: 0, end: 2, priority: 1 },
+            Obj { start: 2, end: 8, priority: 1 },
+            Obj { start: 8, end: 10, priority: 1 },
+        ]);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{create_time_order_events_by, recreate_priority_index_by, Fragment, ObjHolder, TimeOrderIter, Timed};
+
+/// A piece of the final schedule pointing back to the input object it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<U> {
+    /// Position of the original object on the input
+    pub source_index: usize,
+    pub start: U,
+    pub end: U,
+    /// Which part of the original object this is, starting from 1
+    pub part: usize,
+    /// In how many parts the original object was split
+    pub parts_total: usize,
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but instead of clones
+/// of the objects it returns from where each segment came
+///
+/// Useful when 2 objects on the input compare equal, or the objects should not be cloned.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_segments, Segment, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 18, priority: 1 },
+///         Obj { start: 14, end: 15, priority: 2 },
+///     ];
+///
+///     assert_eq!(time_order_by_priority_segments(&prograns), vec![
+///         Segment { source_index: 0, start: 12, end: 14, part: 1, parts_total: 2 },
+///         Segment { source_index: 1, start: 14, end: 15, part: 1, parts_total: 1 },
+///         Segment { source_index: 0, start: 15, end: 18, part: 2, parts_total: 2 },
+///     ]);
+/// ```
+pub fn time_order_by_priority_segments<T, U>(vec: &[T]) -> Vec<Segment<U>>
+where T: Timed<U> + PartialOrd,
+      U: Ord + Copy
+{
+    let (arena, fragments) = borrowed_fragments(vec);
+    into_segments(&arena, fragments)
+}
+
+/// A piece of the final schedule borrowing the input object it came from
+#[derive(Debug, PartialEq)]
+pub struct BorrowedSegment<'a, T, U> {
+    pub item: &'a T,
+    /// Position of the original object on the input
+    pub source_index: usize,
+    pub start: U,
+    pub end: U,
+}
+
+impl<T, U> Clone for BorrowedSegment<'_, T, U>
+where U: Copy
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, U> Copy for BorrowedSegment<'_, T, U>
+where U: Copy
+{}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) for read only objects
+///
+/// The objects are not cloned nor changed, each segment borrows the object it came from
+/// and holds its own start and end, so `T` does not need to be `Clone`.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{resolve, Timed};
+///
+///     #[derive(Debug, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///         payload: Vec<u8>,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1, payload: vec![0; 1024] },
+///         Obj { start: 14, end: 16, priority: 2, payload: vec![1; 1024] },
+///     ];
+///
+///     let segments = resolve(&prograns);
+///     assert_eq!(segments.len(), 2);
+///     assert!(std::ptr::eq(segments[0].item, &prograns[0]));
+///     assert_eq!((segments[0].start, segments[0].end), (12, 14));
+///     assert!(std::ptr::eq(segments[1].item, &prograns[1]));
+///     assert_eq!((segments[1].start, segments[1].end), (14, 16));
+/// ```
+pub fn resolve<T, U>(vec: &[T]) -> Vec<BorrowedSegment<'_, T, U>>
+where T: Timed<U> + PartialOrd,
+      U: Ord + Copy
+{
+    let (arena, fragments) = borrowed_fragments(vec);
+    fragments.into_iter().map(|fragment| {
+        let holder = &arena[fragment.reference];
+        BorrowedSegment {
+            item: holder.obj,
+            source_index: holder.index,
+            start: fragment.start,
+            end: fragment.end,
+        }
+    }).collect()
+}
+
+/// Run the sweep over references to the objects, so nothing is cloned
+fn borrowed_fragments<T, U>(vec: &[T]) -> (Vec<ObjHolder<&T>>, Vec<Fragment<U>>)
+where T: Timed<U> + PartialOrd,
+      U: Ord + Copy
+{
+    let arena = recreate_priority_index_by(vec.iter().collect(), |(_, a), (_, b)| {
+        a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Less)
+    });
+
+    let time_line = create_time_order_events_by(&arena, |obj| (obj.get_start(), obj.get_end()));
+
+    TimeOrderIter::new(arena, time_line).into_fragments()
+}
+
+/// Convert the fragments to segments numbering the parts of each original object
+fn into_segments<T, U>(arena: &[ObjHolder<T>], fragments: Vec<Fragment<U>>) -> Vec<Segment<U>>
+where U: Copy
+{
+    let mut segments: Vec<Segment<U>> = fragments.into_iter().map(|fragment| Segment {
+        source_index: arena[fragment.reference].index,
+        start: fragment.start,
+        end: fragment.end,
+        part: 0,
+        parts_total: 0,
+    }).collect();
+
+    let total = segments.iter().map(|segment| segment.source_index + 1).max().unwrap_or(0);
+    let mut parts_total = vec![0; total];
+    for segment in segments.iter_mut() {
+        parts_total[segment.source_index] += 1;
+        segment.part = parts_total[segment.source_index];
+    }
+    for segment in segments.iter_mut() {
+        segment.parts_total = parts_total[segment.source_index];
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{resolve, time_order_by_priority_segments, Segment, Timed};
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Obj {
+        start: i32,
+        end: i32,
+        priority: i32,
+    }
+
+    impl Timed<i32> for Obj {
+        fn get_start(&self) -> i32 {
+            self.start
+        }
+        fn get_end(&self) -> i32 {
+            self.end
+        }
+        fn set_start(&mut self, time: i32) {
+            self.start = time;
+        }
+        fn set_end(&mut self, time: i32) {
+            self.end = time;
+        }
+    }
+
+    impl PartialOrd for Obj {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.priority.cmp(&other.priority))
+        }
+    }
+
+    /// A and C are equal, only the index can tell them apart (C wins because it is the last)
+    ///
+    ///           |-- B --|
+    ///   |------ A = C ------|
+    ///   0       5       10      15
+    #[test]
+    fn test_equal_objects() {
+        let prograns = vec![
+            Obj { start: 0, end: 15, priority: 1 },
+            Obj { start: 5, end: 10, priority: 2 },
+            Obj { start: 0, end: 15, priority: 1 },
+        ];
+
+        assert_eq!(time_order_by_priority_segments(&prograns), vec![
+            Segment { source_index: 0, start: 0, end: 0, part: 1, parts_total: 1 },
+            Segment { source_index: 2, start: 0, end: 5, part: 1, parts_total: 2 },
+            Segment { source_index: 1, start: 5, end: 10, part: 1, parts_total: 1 },
+            Segment { source_index: 2, start: 10, end: 15, part: 2, parts_total: 2 },
+        ]);
+    }
+
+    /// The result of resolve must be the same of time_order_by_priority_segments
+    #[test]
+    fn test_resolve_borrows_input() {
+        let prograns = vec![
+            Obj { start: 0, end: 15, priority: 1 },
+            Obj { start: 5, end: 10, priority: 2 },
+            Obj { start: 0, end: 15, priority: 1 },
+        ];
+
+        let borrowed = resolve(&prograns);
+        let segments = time_order_by_priority_segments(&prograns);
+        assert_eq!(borrowed.len(), segments.len());
+
+        for (borrowed, segment) in borrowed.iter().zip(segments) {
+            assert!(std::ptr::eq(borrowed.item, &prograns[segment.source_index]));
+            assert_eq!(borrowed.source_index, segment.source_index);
+            assert_eq!((borrowed.start, borrowed.end), (segment.start, segment.end));
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use sortedlist_rs::SortedList;
+
+use crate::{process_end_case, process_start_case, running, Fragment, Handle, ObjHolder, Running, TempObj, TimedEvent, Timed};
+
+/// A incremental version of [`time_order_by_priority`](crate::time_order_by_priority)
+///
+/// The items are given one at a time with [`push`](StreamingScheduler::push) and **must** come
+/// ordered by start time. Finished segments are given back by [`advance`](StreamingScheduler::advance)
+/// once the caller garantee that nothing will start before the watermark.
+///
+/// Only the items that are live (started and not finished) or have segments waiting to be given
+/// back are kept in memory, so the memory is bounded by the maximum overlap and not by the total input.
+/// The scheduler is `Send` when `T` and `U` are, so it can be moved between threads.
+///
+/// The result is the same of the batch version without its zero length segments. The batch version
+/// creates some on ties that the streaming one does not: when a item finish exactly when another
+/// start the End is always processed first, and the items that start at the same time are
+/// processed in the order they are pushed, not by priority. For example pushing (26..34, priority 3)
+/// and then (26..27, priority 2) gives no segment for the second one, while the batch version gives
+/// a zero length one at 26.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{StreamingScheduler, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let mut scheduler = StreamingScheduler::new();
+///     scheduler.push(Obj { start: 12, end: 15, priority: 1 });
+///     scheduler.push(Obj { start: 14, end: 16, priority: 2 });
+///
+///     //A was cut by B at 14
+///     assert_eq!(scheduler.advance(14), vec![Obj { start: 12, end: 14, priority: 1 }]);
+///
+///     assert_eq!(scheduler.finish(), vec![Obj { start: 14, end: 16, priority: 2 }]);
+/// ```
+pub struct StreamingScheduler<T, U> {
+    arena: Vec<ObjHolder<T>>,
+    //positions of the arena that can be reused
+    free: Vec<Handle>,
+    //finished items that can be freed once their segments are given back
+    finished: Vec<Handle>,
+    result: Vec<Fragment<U>>,
+    temp_obj: TempObj<U>,
+    running_prograns: SortedList<Running>,
+    pending_ends: BinaryHeap<Reverse<(U, Handle)>>,
+    watermark: Option<U>,
+    pushed: usize,
+}
+
+impl<T, U> Default for StreamingScheduler<T, U>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U> StreamingScheduler<T, U>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    pub fn new() -> Self {
+        Self {
+            arena: Vec::new(),
+            free: Vec::new(),
+            finished: Vec::new(),
+            result: Vec::new(),
+            temp_obj: None,
+            running_prograns: SortedList::new(),
+            pending_ends: BinaryHeap::new(),
+            watermark: None,
+            pushed: 0,
+        }
+    }
+
+    /// Add a new item on the schedule
+    ///
+    /// Every End before or at the start of the item is processed before it.
+    ///
+    /// # Panics
+    ///
+    /// If the item starts before a item already pushed or before the last watermark
+    pub fn push(&mut self, item: T) {
+        let time = item.get_start();
+
+        self.process_ends_until(time);
+
+        let holder = ObjHolder {
+            obj: item,
+            priority: 0,
+            index: self.pushed,
+        };
+        self.pushed += 1;
+        let reference = match self.free.pop() {
+            Some(reference) => {
+                self.arena[reference] = holder;
+                reference
+            },
+            None => {
+                self.arena.push(holder);
+                self.arena.len() - 1
+            },
+        };
+        self.insert_priority_index(reference);
+
+        self.pending_ends.push(Reverse((self.arena[reference].obj.get_end(), reference)));
+
+        process_start_case(&mut self.result, &mut None, &mut self.temp_obj, &mut self.running_prograns, &self.arena, reference, time);
+    }
+
+    /// Garantee that no item will start before `watermark` and give back all segments
+    /// finished until there
+    ///
+    /// # Panics
+    ///
+    /// If the watermark is before a item already pushed or before the last watermark
+    pub fn advance(&mut self, watermark: U) -> Vec<T> {
+        self.process_ends_until(watermark);
+        let segments = self.result.drain(..).map(|fragment| fragment.into_obj(&self.arena)).collect();
+        self.free.append(&mut self.finished);
+        segments
+    }
+
+    /// Finish all items still running and give back the remaining segments
+    pub fn finish(mut self) -> Vec<T> {
+        while !self.pending_ends.is_empty() {
+            self.process_next_ends();
+        }
+        self.result.iter().map(|fragment| fragment.into_obj(&self.arena)).collect()
+    }
+
+    fn process_ends_until(&mut self, watermark: U) {
+        if let Some(last) = self.watermark {
+            assert!(last <= watermark, "items must be pushed ordered by start time");
+        }
+        self.watermark = Some(watermark);
+
+        while self.pending_ends.peek().is_some_and(|Reverse((time, _))| *time <= watermark) {
+            self.process_next_ends();
+        }
+    }
+
+    /// Process every End on the earliest pending time, the lower priority first, the same
+    /// order `create_time_order_events` gives to the batch version
+    fn process_next_ends(&mut self) {
+        let Some(Reverse((time, reference))) = self.pending_ends.pop() else {
+            return;
+        };
+
+        let mut ends = vec![reference];
+        while self.pending_ends.peek().is_some_and(|Reverse((next, _))| *next == time) {
+            //unwrap is safe because peek just returned a element
+            let Reverse((_, reference)) = self.pending_ends.pop().unwrap();
+            ends.push(reference);
+        }
+        ends.sort_by_key(|reference| self.arena[*reference].priority);
+
+        for reference in ends {
+            process_end_case(&mut self.result, &mut None, &mut self.temp_obj, &mut self.running_prograns, &self.arena,
+                &mut std::iter::empty::<TimedEvent<U>>(), reference, time)
+                //nothing is pulled from a empty iterator so this can not fail
+                .expect("process_end_case failed without a next element");
+            self.finished.push(reference);
+        }
+    }
+
+    /// The batch version can sort all items once, here only the live items are know so the new
+    /// item is placed after every live item with less or equal priority (the last one wins
+    /// on a tie, same as the batch version) and the ones above it are moved one position up.
+    ///
+    /// This keeps the relative order of the live items so the sorted list is still valid
+    fn insert_priority_index(&mut self, reference: Handle) {
+        let mut low = 0;
+        let mut high = self.running_prograns.len();
+        while low < high {
+            let middle = (low + high) / 2;
+            //unwrap is safe because middle < len
+            let (_, item) = self.running_prograns.get(middle).unwrap();
+            let is_less_or_equal = matches!(
+                self.arena[*item].obj.partial_cmp(&self.arena[reference].obj),
+                Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)
+            );
+            if is_less_or_equal {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        self.arena[reference].priority = match self.running_prograns.get(low) {
+            Some((priority, _)) => *priority,
+            None => self.running_prograns.last().map_or(1, |(priority, _)| priority + 1),
+        };
+
+        //the items above are removed and inserted again with the new priority
+        let above: Vec<Running> = (low..self.running_prograns.len())
+            .map(|_| self.running_prograns.remove(low))
+            .collect();
+        for (_, item) in above {
+            self.arena[item].priority += 1;
+            self.running_prograns.insert(running(&self.arena, item));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{time_order_by_priority, StreamingScheduler};
+    use crate::test_util::{complex_example, obj, random_objs, Obj};
+
+    fn streaming(prograns: Vec<Obj>) -> Vec<Obj> {
+        let mut scheduler = StreamingScheduler::new();
+        let mut result = Vec::new();
+        for item in prograns {
+            let start = item.start;
+            scheduler.push(item);
+            result.extend(scheduler.advance(start));
+        }
+        result.extend(scheduler.finish());
+        result
+    }
+
+    fn without_zero_length(segments: Vec<Obj>) -> Vec<Obj> {
+        segments.into_iter().filter(|obj| obj.start < obj.end).collect()
+    }
+
+    #[test]
+    fn test_same_result_as_batch() {
+        assert_eq!(streaming(complex_example()), time_order_by_priority(complex_example()));
+    }
+
+    /// The items that start at the same time are processed on the push order, so the batch
+    /// version can have zero length segments the streaming one does not
+    #[test]
+    fn test_same_start_pushed_out_of_priority_order() {
+        let prograns = vec![obj(26, 34, 3), obj(26, 27, 2)];
+        assert_eq!(time_order_by_priority(prograns.clone()), vec![obj(26, 26, 2), obj(26, 34, 3)]);
+        assert_eq!(streaming(prograns), vec![obj(26, 34, 3)]);
+    }
+
+    #[test]
+    fn test_random_same_result_as_batch_without_zero_length() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..3000 {
+            //pushed on the input order, so the ties are broken the same way
+            let mut prograns = random_objs(&mut rng, 8);
+            prograns.sort_by_key(|obj| obj.start);
+            assert_eq!(
+                without_zero_length(streaming(prograns.clone())),
+                without_zero_length(time_order_by_priority(prograns.clone())),
+                "{prograns:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_advance_only_gives_finished_segments() {
+        let mut scheduler = StreamingScheduler::new();
+        scheduler.push(Obj { start: 0, end: 10, priority: 1 });
+        scheduler.push(Obj { start: 2, end: 4, priority: 2 });
+
+        //A is cut by B at 2 but B and the rest of A are still running
+        assert_eq!(scheduler.advance(3), vec![Obj { start: 0, end: 2, priority: 1 }]);
+        assert_eq!(scheduler.advance(4), vec![Obj { start: 2, end: 4, priority: 2 }]);
+        assert_eq!(scheduler.advance(9), vec![]);
+        assert_eq!(scheduler.finish(), vec![Obj { start: 4, end: 10, priority: 1 }]);
+    }
+
+    #[test]
+    fn test_equal_priority_last_pushed_wins() {
+        let mut scheduler = StreamingScheduler::new();
+        scheduler.push(Obj { start: 0, end: 10, priority: 1 });
+        scheduler.push(Obj { start: 2, end: 6, priority: 2 });
+        scheduler.push(Obj { start: 4, end: 8, priority: 1 });
+
+        assert_eq!(scheduler.finish(), vec![
+            Obj { start: 0, end: 2, priority: 1 },
+            Obj { start: 2, end: 6, priority: 2 },
+            Obj { start: 6, end: 8, priority: 1 },
+            Obj { start: 8, end: 10, priority: 1 },
+        ]);
+    }
+
+    #[test]
+    fn test_send_between_threads() {
+        let mut scheduler = StreamingScheduler::new();
+        scheduler.push(Obj { start: 0, end: 10, priority: 1 });
+
+        let mut scheduler = std::thread::spawn(move || {
+            scheduler.push(Obj { start: 2, end: 4, priority: 2 });
+            scheduler
+        }).join().unwrap();
+
+        scheduler.push(Obj { start: 6, end: 8, priority: 2 });
+        assert_eq!(scheduler.finish(), time_order_by_priority(vec![
+            Obj { start: 0, end: 10, priority: 1 },
+            Obj { start: 2, end: 4, priority: 2 },
+            Obj { start: 6, end: 8, priority: 2 },
+        ]));
+    }
+
+    /// The arena only grows with the overlap, the finished items have their place reused
+    #[test]
+    fn test_arena_reuse() {
+        let mut scheduler = StreamingScheduler::new();
+        for day in 0..100 {
+            scheduler.push(Obj { start: day * 24, end: (day + 1) * 24, priority: 1 });
+            scheduler.advance(day * 24);
+        }
+        assert!(scheduler.arena.len() <= 2);
+        assert_eq!(scheduler.finish(), vec![Obj { start: 99 * 24, end: 100 * 24, priority: 1 }]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_order_push() {
+        let mut scheduler = StreamingScheduler::new();
+        scheduler.push(Obj { start: 5, end: 10, priority: 1 });
+        scheduler.push(Obj { start: 2, end: 4, priority: 2 });
+    }
+}
@@ -37,7 +37,7 @@ mod time_order_by_priority {
     /// This will test if the tester object is working as expected for the test cases
     #[test]
     fn test_obj() {
-        let mut vec = vec![
+        let mut vec = [
             Obj {
                 start: "12:00:00".parse().unwrap(),
                 end: "15:00:00".parse().unwrap(),
@@ -90,9 +90,9 @@ mod time_order_by_priority {
             }
         ];
         
-        let original_obj_prt = prograns.get(0).unwrap() as *const Obj as usize;
+        let original_obj_prt = prograns.first().unwrap() as *const Obj as usize;
         let ordered = time_order_by_priority(prograns);
-        assert!(original_obj_prt == ordered.get(0).unwrap() as *const Obj as usize);
+        assert!(original_obj_prt == ordered.first().unwrap() as *const Obj as usize);
     }
 
     /// This test has 2 objects with a overlap of 1 hour (14:00 to 15:00)
@@ -127,7 +127,7 @@ mod time_order_by_priority {
 
         let ordered = time_order_by_priority(prograns);
         assert_eq!(ordered.len(), 2);
-        let obj = ordered.get(0).unwrap();
+        let obj = ordered.first().unwrap();
         assert!(obj.description == "A");
         assert!(obj.start == "12:00:00".parse().unwrap());
         assert!(obj.end == "14:00:00".parse().unwrap());
@@ -167,7 +167,7 @@ mod time_order_by_priority {
 
         let ordered = time_order_by_priority(prograns);
         assert_eq!(ordered.len(), 2);
-        let obj = ordered.get(0).unwrap();
+        let obj = ordered.first().unwrap();
         assert!(obj.description == "A");
         assert!(obj.start == "12:00:00".parse().unwrap());
         assert!(obj.end == "15:00:00".parse().unwrap());
@@ -205,7 +205,7 @@ mod time_order_by_priority {
 
         let ordered = time_order_by_priority(prograns);
         assert_eq!(ordered.len(), 2);
-        let obj = ordered.get(0).unwrap();
+        let obj = ordered.first().unwrap();
         assert!(obj.description == "A");
         assert!(obj.start == "12:00:00".parse().unwrap());
         assert!(obj.end == "13:00:00".parse().unwrap());
@@ -257,7 +257,7 @@ mod time_order_by_priority {
         let ordered = time_order_by_priority(prograns);
 
         assert_eq!(ordered.len(), 3);
-        let obj = ordered.get(0).unwrap();
+        let obj = ordered.first().unwrap();
         assert!(obj.description == "A");
         assert!(obj.start == "12:00:00".parse().unwrap());
         assert!(obj.end == "14:00:00".parse().unwrap());
@@ -307,7 +307,7 @@ mod time_order_by_priority {
         let ordered = time_order_by_priority(prograns);
 
         assert_eq!(ordered.len(), 3);
-        let obj = ordered.get(0).unwrap();
+        let obj = ordered.first().unwrap();
         assert!(obj.description == "A");
         assert!(obj.start == "12:00:00".parse().unwrap());
         assert!(obj.end == "14:00:00".parse().unwrap());
@@ -343,7 +343,7 @@ mod time_order_by_priority {
                 start: "12:00:00".parse().unwrap(),
                 end: "18:00:00".parse().unwrap(),
                 description: "A".to_string(),
-                priority: std::i32::MAX,
+                priority: i32::MAX,
             },
             Obj {
                 start: "14:00:00".parse().unwrap(),
@@ -356,7 +356,7 @@ mod time_order_by_priority {
         let ordered = time_order_by_priority(prograns);
 
         assert_eq!(ordered.len(), 1);
-        let obj = ordered.get(0).unwrap();
+        let obj = ordered.first().unwrap();
         assert!(obj.description == "A");
         assert!(obj.start == "12:00:00".parse().unwrap());
         assert!(obj.end == "18:00:00".parse().unwrap());
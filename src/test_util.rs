@@ -0,0 +1,91 @@
+//! The objects shared by the tests of the modules
+
+use rand::{rngs::StdRng, Rng};
+
+use crate::Timed;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Obj {
+    pub start: i32,
+    pub end: i32,
+    pub priority: i32,
+}
+
+impl Timed<i32> for Obj {
+    fn get_start(&self) -> i32 {
+        self.start
+    }
+    fn get_end(&self) -> i32 {
+        self.end
+    }
+    fn set_start(&mut self, time: i32) {
+        self.start = time;
+    }
+    fn set_end(&mut self, time: i32) {
+        self.end = time;
+    }
+}
+
+impl PartialOrd for Obj {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.priority.cmp(&other.priority))
+    }
+}
+
+pub(crate) fn obj(start: i32, end: i32, priority: i32) -> Obj {
+    Obj { start, end, priority }
+}
+
+/// Same as the complex example of test_example.rs but with integer times,
+/// on the input order F, A, C, D, B and E
+pub(crate) fn complex_example() -> Vec<Obj> {
+    vec![
+        obj(1100, 1130, 1),
+        obj(1200, 1500, 1),
+        obj(1230, 1430, 3),
+        obj(1300, 1400, 4),
+        obj(1330, 1530, 2),
+        obj(1600, 1630, 1),
+    ]
+}
+
+/// Up to `len` objects with small times and priorities, so there are many ties,
+/// touching and zero length objects
+pub(crate) fn random_objs(rng: &mut StdRng, len: i32) -> Vec<Obj> {
+    (0..rng.gen_range(1..=len)).map(|_| {
+        let start = rng.gen_range(0..40);
+        obj(start, start + rng.gen_range(0..10), rng.gen_range(0..4))
+    }).collect()
+}
+
+/// A [`Obj`] with something more, for the tests that need more than the priority
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Tagged<X> {
+    pub obj: Obj,
+    pub tag: X,
+}
+
+impl<X> Timed<i32> for Tagged<X> {
+    fn get_start(&self) -> i32 {
+        self.obj.start
+    }
+    fn get_end(&self) -> i32 {
+        self.obj.end
+    }
+    fn set_start(&mut self, time: i32) {
+        self.obj.start = time;
+    }
+    fn set_end(&mut self, time: i32) {
+        self.obj.end = time;
+    }
+}
+
+impl<X: PartialEq> PartialOrd for Tagged<X> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.obj.partial_cmp(&other.obj)
+    }
+}
+
+pub(crate) fn tagged<X>(start: i32, end: i32, priority: i32, tag: X) -> Tagged<X> {
+    Tagged { obj: obj(start, end, priority), tag }
+}
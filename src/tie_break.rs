@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+
+use crate::{create_time_order_events, recreate_priority_index_by, Timed, TimeOrderIter};
+
+/// A user comparator, the object compared as `Greater` wins
+pub type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+/// Which object wins when 2 objects have the same priority (`partial_cmp` gives `Equal`)
+///
+/// The sort used to create the priorities is stable, so when the tie break also gives
+/// `Equal` the object that comes last on the input wins. The result is always the same
+/// for the same input.
+#[derive(Default)]
+pub enum TieBreak<T> {
+    /// The object that started first wins
+    FirstStarted,
+    /// The object that started last wins
+    LatestStarted,
+    /// The object that comes last on the input wins, this is what
+    /// [`time_order_by_priority`](crate::time_order_by_priority) does
+    #[default]
+    InputOrder,
+    /// The object with less time remaining wins, so the one that finishes first
+    ShortestRemaining,
+    /// The object compared as `Greater` wins
+    Custom(Comparator<T>),
+}
+
+impl<T> TieBreak<T> {
+    /// Compare 2 objects with the same priority, the `Greater` one wins
+    fn compare<U>(&self, (index_a, a): (usize, &T), (index_b, b): (usize, &T)) -> Ordering
+    where T: Timed<U>,
+          U: Ord + Copy
+    {
+        match self {
+            TieBreak::FirstStarted => b.get_start().cmp(&a.get_start()),
+            TieBreak::LatestStarted => a.get_start().cmp(&b.get_start()),
+            TieBreak::InputOrder => index_a.cmp(&index_b),
+            TieBreak::ShortestRemaining => b.get_end().cmp(&a.get_end()),
+            TieBreak::Custom(compare) => compare(a, b),
+        }
+    }
+
+    /// Compare by priority and on a tie by this policy
+    pub(crate) fn compare_priority<U>(&self, a: (usize, &T), b: (usize, &T)) -> Ordering
+    where T: Timed<U> + PartialOrd,
+          U: Ord + Copy
+    {
+        match a.1.partial_cmp(b.1).unwrap_or(Ordering::Less) {
+            Ordering::Equal => self.compare(a, b),
+            ordering => ordering,
+        }
+    }
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but the objects with the
+/// same priority are ordered by `tie_break`
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_with_tie_break, TieBreak, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 1 },
+///     ];
+///
+///     //A started first so it is not cut by B
+///     let ordered = time_order_by_priority_with_tie_break(prograns, TieBreak::FirstStarted);
+///     assert_eq!(ordered, vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 15, end: 16, priority: 1 },
+///     ]);
+/// ```
+pub fn time_order_by_priority_with_tie_break<T, U>(vec: Vec::<T>, tie_break: TieBreak<T>) -> Vec<T>
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    let arena = recreate_priority_index_by(vec, |(index_a, a), (index_b, b)| {
+        tie_break.compare_priority((*index_a, a), (*index_b, b))
+    });
+
+    let time_line = create_time_order_events(&arena);
+
+    TimeOrderIter::new(arena, time_line).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_with_tie_break, TieBreak};
+    use crate::test_util::{tagged, Tagged};
+
+    ///       |------ B ------|
+    ///   |---------- A ----------|
+    ///   0   2               8   10
+    ///
+    /// A and B with the same priority, B is the first on the input
+    fn prograns() -> Vec<Tagged<char>> {
+        vec![
+            tagged(2, 8, 1, 'B'),
+            tagged(0, 10, 1, 'A'),
+        ]
+    }
+
+    fn names(vec: Vec<Tagged<char>>) -> Vec<(char, i32, i32)> {
+        vec.into_iter().map(|item| (item.tag, item.obj.start, item.obj.end)).collect()
+    }
+
+    #[test]
+    fn test_first_started() {
+        let ordered = time_order_by_priority_with_tie_break(prograns(), TieBreak::FirstStarted);
+        assert_eq!(names(ordered), vec![('A', 0, 10)]);
+    }
+
+    #[test]
+    fn test_latest_started() {
+        let ordered = time_order_by_priority_with_tie_break(prograns(), TieBreak::LatestStarted);
+        assert_eq!(names(ordered), vec![('A', 0, 2), ('B', 2, 8), ('A', 8, 10)]);
+    }
+
+    #[test]
+    fn test_input_order() {
+        let ordered = time_order_by_priority_with_tie_break(prograns(), TieBreak::InputOrder);
+        assert_eq!(ordered, time_order_by_priority(prograns()));
+        assert_eq!(names(ordered), vec![('A', 0, 10)]);
+    }
+
+    #[test]
+    fn test_shortest_remaining() {
+        let ordered = time_order_by_priority_with_tie_break(prograns(), TieBreak::ShortestRemaining);
+        assert_eq!(names(ordered), vec![('A', 0, 2), ('B', 2, 8), ('A', 8, 10)]);
+    }
+
+    #[test]
+    fn test_custom() {
+        let tie_break = TieBreak::Custom(Box::new(|a: &Tagged<char>, b: &Tagged<char>| a.tag.cmp(&b.tag)));
+        let ordered = time_order_by_priority_with_tie_break(prograns(), tie_break);
+        assert_eq!(names(ordered), vec![('A', 0, 2), ('B', 2, 8), ('A', 8, 10)]);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{create_time_order_events, iter::Sweep, recreate_priority_index, Handle, ObjHolder, TimedEvent, Timed, Trace};
+
+/// Why the object playing changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecisionKind {
+    /// Nothing was playing and the winner started
+    Started,
+    /// The winner started with a higher priority and cut the loser
+    Preempted,
+    /// The loser finished and the winner, that was waiting under it, plays. The winner may play
+    /// for the first time, when it started while the loser was playing
+    Resumed,
+    /// The loser finished and nothing else is waiting, so nothing plays
+    Ended,
+}
+
+/// A change of the object playing, the objects are given by their position on the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decision<U> {
+    pub time: U,
+    pub kind: DecisionKind,
+    /// The object that plays from `time`, `None` when nothing plays
+    pub winner: Option<usize>,
+    /// The object that stops playing at `time`, `None` when nothing was playing
+    pub loser: Option<usize>,
+}
+
+/// Save a decision on `trace` if it is being recorded
+pub(crate) fn record<T, U>(
+    trace: &mut Trace<U>,
+    arena: &[ObjHolder<T>],
+    time: U,
+    kind: DecisionKind,
+    winner: Option<Handle>,
+    loser: Option<Handle>)
+{
+    if let Some(trace) = trace {
+        trace.push(Decision {
+            time,
+            kind,
+            winner: winner.map(|reference| arena[reference].index),
+            loser: loser.map(|reference| arena[reference].index),
+        });
+    }
+}
+
+/// Same as [`time_order_by_priority`](crate::time_order_by_priority) but also gives back every
+/// decision taken, in the order they were taken, so it is possible to explain why a object was cut
+///
+/// A object that is never played (always under a higher priority) has no decision.
+///
+/// ```rust
+///     use time_priority_order_algoritmh::{time_order_by_priority_traced, Decision, DecisionKind, Timed};
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     struct Obj {
+///         start: i32,
+///         end: i32,
+///         priority: i32,
+///     }
+///
+///     impl Timed<i32> for Obj {
+///         fn get_start(&self) -> i32 { self.start }
+///         fn get_end(&self) -> i32 { self.end }
+///         fn set_start(&mut self, time: i32) { self.start = time; }
+///         fn set_end(&mut self, time: i32) { self.end = time; }
+///     }
+///
+///     impl PartialOrd for Obj {
+///         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+///             Some(self.priority.cmp(&other.priority))
+///         }
+///     }
+///
+///     let prograns = vec![
+///         Obj { start: 12, end: 15, priority: 1 },
+///         Obj { start: 14, end: 16, priority: 2 },
+///     ];
+///
+///     let (ordered, trace) = time_order_by_priority_traced(prograns);
+///     assert_eq!(ordered.len(), 2);
+///     assert_eq!(trace, vec![
+///         Decision { time: 12, kind: DecisionKind::Started, winner: Some(0), loser: None },
+///         Decision { time: 14, kind: DecisionKind::Preempted, winner: Some(1), loser: Some(0) },
+///         Decision { time: 16, kind: DecisionKind::Ended, winner: None, loser: Some(1) },
+///     ]);
+/// ```
+pub fn time_order_by_priority_traced<T, U>(vec: Vec::<T>) -> (Vec<T>, Vec<Decision<U>>)
+where T: Timed<U> + PartialOrd + Clone,
+      U: Ord + Copy
+{
+    let arena: Vec<ObjHolder<T>> = recreate_priority_index(vec);
+
+    let time_line: Vec<TimedEvent<U>> = create_time_order_events(&arena);
+
+    let mut sweep = Sweep::traced(time_line);
+    let result = std::iter::from_fn(|| sweep.next_fragment(&arena))
+        .map(|fragment| fragment.into_obj(&arena))
+        .collect();
+
+    (result, sweep.take_trace())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{time_order_by_priority, time_order_by_priority_traced, Decision, DecisionKind};
+    use crate::test_util::{complex_example, Obj};
+
+    fn decision(time: i32, kind: DecisionKind, winner: Option<usize>, loser: Option<usize>) -> Decision<i32> {
+        Decision { time, kind, winner, loser }
+    }
+
+    ///       |- B -|               |- D -|
+    ///   |------ A ------|     |---- C ----|
+    ///   0   2      4    8    10  12    14 16
+    ///
+    /// D has a lower priority than C, so it is never played
+    #[test]
+    fn test_trace() {
+        let prograns = vec![
+            Obj { start: 0, end: 8, priority: 1 },
+            Obj { start: 2, end: 4, priority: 2 },
+            Obj { start: 10, end: 16, priority: 2 },
+            Obj { start: 12, end: 14, priority: 1 },
+        ];
+
+        let (ordered, trace) = time_order_by_priority_traced(prograns.clone());
+        assert_eq!(ordered, time_order_by_priority(prograns));
+        assert_eq!(trace, vec![
+            decision(0, DecisionKind::Started, Some(0), None),
+            decision(2, DecisionKind::Preempted, Some(1), Some(0)),
+            decision(4, DecisionKind::Resumed, Some(0), Some(1)),
+            decision(8, DecisionKind::Ended, None, Some(0)),
+            decision(10, DecisionKind::Started, Some(2), None),
+            decision(16, DecisionKind::Ended, None, Some(2)),
+        ]);
+    }
+
+    ///   |-------- A --------|
+    ///       |-------- B --------|
+    ///   0   2              10   12
+    ///
+    /// B waits under A since 2, so it is resumed at 10 even if it never played before
+    #[test]
+    fn test_resumed_for_the_first_time() {
+        let prograns = vec![
+            Obj { start: 0, end: 10, priority: 3 },
+            Obj { start: 2, end: 12, priority: 1 },
+        ];
+
+        let (_, trace) = time_order_by_priority_traced(prograns);
+        assert_eq!(trace, vec![
+            decision(0, DecisionKind::Started, Some(0), None),
+            decision(10, DecisionKind::Resumed, Some(1), Some(0)),
+            decision(12, DecisionKind::Ended, None, Some(1)),
+        ]);
+    }
+
+    #[test]
+    fn test_one_decision_per_fragment_boundary() {
+        let prograns = complex_example();
+
+        let (ordered, trace) = time_order_by_priority_traced(prograns);
+
+        //every segment starts with a decision that gives it the winner
+        let winners: Vec<i32> = trace.iter().filter(|decision| decision.winner.is_some()).map(|decision| decision.time).collect();
+        let starts: Vec<i32> = ordered.iter().map(|obj| obj.start).collect();
+        assert_eq!(winners, starts);
+    }
+}